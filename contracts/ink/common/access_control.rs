@@ -0,0 +1,232 @@
+use ink::primitives::AccountId;
+use ink::storage::Mapping;
+use scale::{Decode, Encode};
+
+type Environment = ink::env::DefaultEnvironment;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Role {
+    Admin,
+    Registrar,
+    FleetManager,
+    TelemetryValidator,
+    Auditor,
+    /// May pause, but not unpause, contract functionality.
+    Guardian,
+    /// May service vehicles whose owner or a fleet manager has approved the visit.
+    ServiceProvider,
+}
+
+impl Role {
+    pub const ALL: [Role; 7] = [
+        Role::Admin,
+        Role::Registrar,
+        Role::FleetManager,
+        Role::TelemetryValidator,
+        Role::Auditor,
+        Role::Guardian,
+        Role::ServiceProvider,
+    ];
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AccessControlError {
+    MissingRole,
+}
+
+#[ink::event]
+pub struct RoleGranted {
+    #[ink(topic)]
+    pub role: Role,
+    #[ink(topic)]
+    pub account: AccountId,
+    #[ink(topic)]
+    pub sender: AccountId,
+}
+
+#[ink::event]
+pub struct RoleRevoked {
+    #[ink(topic)]
+    pub role: Role,
+    #[ink(topic)]
+    pub account: AccountId,
+    #[ink(topic)]
+    pub sender: AccountId,
+}
+
+#[ink::event]
+pub struct RoleAdminChanged {
+    #[ink(topic)]
+    pub role: Role,
+    pub previous_admin_role: Role,
+    pub new_admin_role: Role,
+}
+
+/// Role membership embedded in a contract's storage. Every role is administered
+/// by `Role::Admin` unless reassigned with `set_role_admin`.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct AccessControl {
+    members: Mapping<(Role, AccountId), ()>,
+    role_admins: Mapping<Role, Role>,
+}
+
+impl AccessControl {
+    /// Grants every role to `account`; used by constructors for the deploying account.
+    pub fn with_all_roles(account: AccountId) -> Self {
+        let mut access = Self::default();
+        for role in Role::ALL {
+            access.insert(role, account, account);
+        }
+        access
+    }
+
+    pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+        self.members.contains((role, account))
+    }
+
+    pub fn ensure_role(&self, role: Role, account: AccountId) -> Result<(), AccessControlError> {
+        if !self.has_role(role, account) {
+            return Err(AccessControlError::MissingRole);
+        }
+        Ok(())
+    }
+
+    pub fn role_admin(&self, role: Role) -> Role {
+        self.role_admins.get(role).unwrap_or(Role::Admin)
+    }
+
+    pub fn grant_role(&mut self, caller: AccountId, role: Role, account: AccountId) -> Result<(), AccessControlError> {
+        self.ensure_role(self.role_admin(role), caller)?;
+        self.insert(role, account, caller);
+        Ok(())
+    }
+
+    pub fn revoke_role(&mut self, caller: AccountId, role: Role, account: AccountId) -> Result<(), AccessControlError> {
+        self.ensure_role(self.role_admin(role), caller)?;
+        self.remove(role, account, caller);
+        Ok(())
+    }
+
+    pub fn renounce_role(&mut self, caller: AccountId, role: Role) -> Result<(), AccessControlError> {
+        self.ensure_role(role, caller)?;
+        self.remove(role, caller, caller);
+        Ok(())
+    }
+
+    pub fn set_role_admin(&mut self, caller: AccountId, role: Role, admin_role: Role) -> Result<(), AccessControlError> {
+        let previous_admin_role = self.role_admin(role);
+        self.ensure_role(previous_admin_role, caller)?;
+        self.role_admins.insert(role, &admin_role);
+
+        ink::env::emit_event::<Environment, _>(RoleAdminChanged {
+            role,
+            previous_admin_role,
+            new_admin_role: admin_role,
+        });
+        Ok(())
+    }
+
+    /// Moves every role held by `from` to `to`, or drops them when `to` is `None`.
+    /// Used when contract ownership changes hands.
+    pub fn transfer_all_roles(&mut self, from: AccountId, to: Option<AccountId>) {
+        for role in Role::ALL {
+            if !self.has_role(role, from) {
+                continue;
+            }
+            self.remove(role, from, from);
+            if let Some(to) = to {
+                self.insert(role, to, from);
+            }
+        }
+    }
+
+    fn insert(&mut self, role: Role, account: AccountId, sender: AccountId) {
+        if self.has_role(role, account) {
+            return;
+        }
+        self.members.insert((role, account), &());
+        ink::env::emit_event::<Environment, _>(RoleGranted { role, account, sender });
+    }
+
+    fn remove(&mut self, role: Role, account: AccountId, sender: AccountId) {
+        if !self.has_role(role, account) {
+            return;
+        }
+        self.members.remove((role, account));
+        ink::env::emit_event::<Environment, _>(RoleRevoked { role, account, sender });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts() -> ink::env::test::DefaultAccounts<Environment> {
+        ink::env::test::default_accounts::<Environment>()
+    }
+
+    #[ink::test]
+    fn admin_grants_and_revokes_roles() {
+        let accounts = accounts();
+        let mut access = AccessControl::with_all_roles(accounts.alice);
+
+        access.grant_role(accounts.alice, Role::Registrar, accounts.bob).unwrap();
+        assert!(access.has_role(Role::Registrar, accounts.bob));
+        assert_eq!(
+            access.grant_role(accounts.bob, Role::Registrar, accounts.charlie),
+            Err(AccessControlError::MissingRole)
+        );
+
+        access.revoke_role(accounts.alice, Role::Registrar, accounts.bob).unwrap();
+        assert!(!access.has_role(Role::Registrar, accounts.bob));
+    }
+
+    #[ink::test]
+    fn role_admin_hierarchy_is_respected() {
+        let accounts = accounts();
+        let mut access = AccessControl::with_all_roles(accounts.alice);
+        access.grant_role(accounts.alice, Role::FleetManager, accounts.bob).unwrap();
+
+        access.set_role_admin(accounts.alice, Role::TelemetryValidator, Role::FleetManager).unwrap();
+        assert_eq!(access.role_admin(Role::TelemetryValidator), Role::FleetManager);
+
+        access.grant_role(accounts.bob, Role::TelemetryValidator, accounts.charlie).unwrap();
+        assert!(access.has_role(Role::TelemetryValidator, accounts.charlie));
+        assert_eq!(
+            access.grant_role(accounts.bob, Role::Auditor, accounts.charlie),
+            Err(AccessControlError::MissingRole)
+        );
+    }
+
+    #[ink::test]
+    fn members_can_renounce_their_own_roles() {
+        let accounts = accounts();
+        let mut access = AccessControl::with_all_roles(accounts.alice);
+
+        assert_eq!(access.renounce_role(accounts.bob, Role::Auditor), Err(AccessControlError::MissingRole));
+        access.renounce_role(accounts.alice, Role::Auditor).unwrap();
+        assert!(!access.has_role(Role::Auditor, accounts.alice));
+        assert!(access.has_role(Role::Admin, accounts.alice));
+    }
+
+    #[ink::test]
+    fn roles_move_with_ownership() {
+        let accounts = accounts();
+        let mut access = AccessControl::with_all_roles(accounts.alice);
+        access.renounce_role(accounts.alice, Role::Auditor).unwrap();
+
+        access.transfer_all_roles(accounts.alice, Some(accounts.bob));
+        assert!(access.has_role(Role::Admin, accounts.bob));
+        assert!(!access.has_role(Role::Auditor, accounts.bob));
+        assert!(!access.has_role(Role::Admin, accounts.alice));
+
+        access.transfer_all_roles(accounts.bob, None);
+        assert!(Role::ALL.iter().all(|role| !access.has_role(*role, accounts.bob)));
+    }
+}
\ No newline at end of file
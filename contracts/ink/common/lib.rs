@@ -0,0 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod access_control;
+pub mod ownable;
+pub mod pausable;
+pub mod upgradeable;
+
+pub use access_control::{AccessControl, AccessControlError, Role, RoleAdminChanged, RoleGranted, RoleRevoked};
+pub use ownable::{Ownable, OwnableError, OwnershipTransferStarted, OwnershipTransferred};
+pub use pausable::{PauseScope, Pausable, PausableError, Paused, Unpaused};
+pub use upgradeable::{Migrate, StorageMigrated, Upgraded, VersionedMapping, VersionedValue};
\ No newline at end of file
@@ -0,0 +1,132 @@
+use ink::primitives::AccountId;
+use scale::{Decode, Encode};
+
+type Environment = ink::env::DefaultEnvironment;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum OwnableError {
+    NotOwner,
+    NotPendingOwner,
+}
+
+#[ink::event]
+pub struct OwnershipTransferStarted {
+    #[ink(topic)]
+    pub previous_owner: AccountId,
+    #[ink(topic)]
+    pub new_owner: AccountId,
+}
+
+#[ink::event]
+pub struct OwnershipTransferred {
+    #[ink(topic)]
+    pub previous_owner: Option<AccountId>,
+    #[ink(topic)]
+    pub new_owner: Option<AccountId>,
+}
+
+/// Contract ownership with a two-step handover: the proposed owner has to accept
+/// before anything changes, so a typo in the address cannot lock the contract.
+#[ink::storage_item]
+#[derive(Debug)]
+pub struct Ownable {
+    owner: Option<AccountId>,
+    pending_owner: Option<AccountId>,
+}
+
+impl Ownable {
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner: Some(owner),
+            pending_owner: None,
+        }
+    }
+
+    pub fn owner(&self) -> Option<AccountId> {
+        self.owner
+    }
+
+    pub fn pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner
+    }
+
+    pub fn ensure_owner(&self, caller: AccountId) -> Result<(), OwnableError> {
+        if self.owner != Some(caller) {
+            return Err(OwnableError::NotOwner);
+        }
+        Ok(())
+    }
+
+    /// Proposes `new_owner`, replacing any earlier proposal.
+    pub fn transfer_ownership(&mut self, caller: AccountId, new_owner: AccountId) -> Result<(), OwnableError> {
+        self.ensure_owner(caller)?;
+        self.pending_owner = Some(new_owner);
+
+        ink::env::emit_event::<Environment, _>(OwnershipTransferStarted {
+            previous_owner: caller,
+            new_owner,
+        });
+        Ok(())
+    }
+
+    /// Completes a pending transfer and returns the previous owner.
+    pub fn accept_ownership(&mut self, caller: AccountId) -> Result<Option<AccountId>, OwnableError> {
+        if self.pending_owner != Some(caller) {
+            return Err(OwnableError::NotPendingOwner);
+        }
+
+        let previous_owner = self.owner.replace(caller);
+        self.pending_owner = None;
+
+        ink::env::emit_event::<Environment, _>(OwnershipTransferred {
+            previous_owner,
+            new_owner: Some(caller),
+        });
+        Ok(previous_owner)
+    }
+
+    pub fn renounce_ownership(&mut self, caller: AccountId) -> Result<(), OwnableError> {
+        self.ensure_owner(caller)?;
+        self.owner = None;
+        self.pending_owner = None;
+
+        ink::env::emit_event::<Environment, _>(OwnershipTransferred {
+            previous_owner: Some(caller),
+            new_owner: None,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[ink::test]
+    fn ownership_changes_only_after_acceptance() {
+        let accounts = ink::env::test::default_accounts::<Environment>();
+        let mut ownable = Ownable::new(accounts.alice);
+
+        assert_eq!(ownable.transfer_ownership(accounts.bob, accounts.bob), Err(OwnableError::NotOwner));
+        ownable.transfer_ownership(accounts.alice, accounts.bob).unwrap();
+        assert_eq!(ownable.owner(), Some(accounts.alice));
+        assert_eq!(ownable.pending_owner(), Some(accounts.bob));
+
+        assert_eq!(ownable.accept_ownership(accounts.charlie), Err(OwnableError::NotPendingOwner));
+        assert_eq!(ownable.accept_ownership(accounts.bob), Ok(Some(accounts.alice)));
+        assert_eq!(ownable.owner(), Some(accounts.bob));
+        assert_eq!(ownable.pending_owner(), None);
+    }
+
+    #[ink::test]
+    fn renounce_clears_owner_and_pending_transfer() {
+        let accounts = ink::env::test::default_accounts::<Environment>();
+        let mut ownable = Ownable::new(accounts.alice);
+        ownable.transfer_ownership(accounts.alice, accounts.bob).unwrap();
+
+        ownable.renounce_ownership(accounts.alice).unwrap();
+        assert_eq!(ownable.owner(), None);
+        assert_eq!(ownable.accept_ownership(accounts.bob), Err(OwnableError::NotPendingOwner));
+    }
+}
\ No newline at end of file
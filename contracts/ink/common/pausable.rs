@@ -0,0 +1,111 @@
+use ink::primitives::AccountId;
+use scale::{Decode, Encode};
+
+type Environment = ink::env::DefaultEnvironment;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PauseScope {
+    /// Creating new records.
+    Registration,
+    /// Changing existing records.
+    Mutation,
+    /// Paged and scanning queries.
+    Query,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PausableError {
+    Paused,
+}
+
+#[ink::event]
+pub struct Paused {
+    #[ink(topic)]
+    pub scope: PauseScope,
+    #[ink(topic)]
+    pub account: AccountId,
+}
+
+#[ink::event]
+pub struct Unpaused {
+    #[ink(topic)]
+    pub scope: PauseScope,
+    #[ink(topic)]
+    pub account: AccountId,
+}
+
+/// Independent pause flags per `PauseScope`. Who may flip them is up to the contract;
+/// pausing and unpausing are idempotent and only emit an event on an actual change.
+/// Contracts keep it in a `Lazy` cell so the flags stay out of the root struct.
+#[derive(Encode, Decode, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Pausable {
+    registration: bool,
+    mutation: bool,
+    query: bool,
+}
+
+impl Pausable {
+    pub fn is_paused(&self, scope: PauseScope) -> bool {
+        match scope {
+            PauseScope::Registration => self.registration,
+            PauseScope::Mutation => self.mutation,
+            PauseScope::Query => self.query,
+        }
+    }
+
+    pub fn ensure_not_paused(&self, scope: PauseScope) -> Result<(), PausableError> {
+        if self.is_paused(scope) {
+            return Err(PausableError::Paused);
+        }
+        Ok(())
+    }
+
+    pub fn pause(&mut self, scope: PauseScope, account: AccountId) {
+        if self.set(scope, true) {
+            ink::env::emit_event::<Environment, _>(Paused { scope, account });
+        }
+    }
+
+    pub fn unpause(&mut self, scope: PauseScope, account: AccountId) {
+        if self.set(scope, false) {
+            ink::env::emit_event::<Environment, _>(Unpaused { scope, account });
+        }
+    }
+
+    fn set(&mut self, scope: PauseScope, paused: bool) -> bool {
+        let flag = match scope {
+            PauseScope::Registration => &mut self.registration,
+            PauseScope::Mutation => &mut self.mutation,
+            PauseScope::Query => &mut self.query,
+        };
+        let changed = *flag != paused;
+        *flag = paused;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[ink::test]
+    fn scopes_are_paused_independently() {
+        let accounts = ink::env::test::default_accounts::<Environment>();
+        let mut pausable = Pausable::default();
+
+        pausable.pause(PauseScope::Registration, accounts.alice);
+        assert_eq!(pausable.ensure_not_paused(PauseScope::Registration), Err(PausableError::Paused));
+        assert_eq!(pausable.ensure_not_paused(PauseScope::Mutation), Ok(()));
+        assert!(!pausable.is_paused(PauseScope::Query));
+
+        // Repeated calls are no-ops.
+        pausable.pause(PauseScope::Registration, accounts.bob);
+        pausable.unpause(PauseScope::Registration, accounts.alice);
+        pausable.unpause(PauseScope::Registration, accounts.alice);
+        assert!(!pausable.is_paused(PauseScope::Registration));
+        assert_eq!(ink::env::test::recorded_events().count(), 2);
+    }
+}
\ No newline at end of file
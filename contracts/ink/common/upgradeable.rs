@@ -0,0 +1,202 @@
+use core::marker::PhantomData;
+use ink::prelude::vec::Vec;
+use ink::primitives::{Hash, Key};
+use ink::storage::traits::{AutoKey, Storable, StorableHint, StorageKey};
+use ink::storage::Mapping;
+use scale::{Decode, Encode, EncodeLike, Error, Input, Output};
+
+/// A record type kept in a `VersionedMapping`.
+///
+/// Bump `VERSION` whenever the SCALE layout changes and teach `migrate` how to read
+/// the payloads written by earlier versions. Old entries are converted when they are
+/// read and written back in the current layout the next time they are stored.
+pub trait Migrate: Encode + Decode {
+    const VERSION: u16;
+
+    fn migrate(version: u16, payload: &[u8]) -> Option<Self> {
+        let _ = (version, payload);
+        None
+    }
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct VersionedValue {
+    pub version: u16,
+    pub payload: Vec<u8>,
+}
+
+impl VersionedValue {
+    pub fn new<T: Migrate>(value: &T) -> Self {
+        Self {
+            version: T::VERSION,
+            payload: value.encode(),
+        }
+    }
+
+    pub fn decode<T: Migrate>(&self) -> Option<T> {
+        if self.version == T::VERSION {
+            T::decode(&mut &self.payload[..]).ok()
+        } else {
+            T::migrate(self.version, &self.payload)
+        }
+    }
+}
+
+/// A `Mapping` whose values carry their layout version.
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+#[cfg_attr(feature = "std", scale_info(skip_type_params(T)))]
+pub struct VersionedMapping<K, T, KeyType: StorageKey = AutoKey> {
+    inner: Mapping<K, VersionedValue, KeyType>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<K, T, KeyType: StorageKey> Default for VersionedMapping<K, T, KeyType> {
+    fn default() -> Self {
+        Self {
+            inner: Mapping::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, T, KeyType: StorageKey> core::fmt::Debug for VersionedMapping<K, T, KeyType> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("VersionedMapping").field("key", &KeyType::KEY).finish()
+    }
+}
+
+impl<K, T, KeyType> VersionedMapping<K, T, KeyType>
+where
+    K: Encode,
+    T: Migrate,
+    KeyType: StorageKey,
+{
+    pub fn insert<Q: EncodeLike<K>>(&mut self, key: Q, value: &T) -> Option<u32> {
+        self.inner.insert(key, &VersionedValue::new(value))
+    }
+
+    /// # Panics
+    ///
+    /// Traps if the stored payload can neither be decoded nor migrated.
+    pub fn get<Q: EncodeLike<K>>(&self, key: Q) -> Option<T> {
+        self.inner.get(key).map(|stored| {
+            stored
+                .decode()
+                .unwrap_or_else(|| panic!("Failed to migrate value from version {}", stored.version))
+        })
+    }
+
+    pub fn contains<Q: EncodeLike<K>>(&self, key: Q) -> bool {
+        self.inner.contains(key)
+    }
+
+    pub fn remove<Q: EncodeLike<K>>(&self, key: Q) {
+        self.inner.remove(key)
+    }
+}
+
+impl<K, T, KeyType: StorageKey> Storable for VersionedMapping<K, T, KeyType> {
+    fn encode<O: Output + ?Sized>(&self, _dest: &mut O) {}
+
+    fn decode<I: Input>(_input: &mut I) -> Result<Self, Error> {
+        Ok(Default::default())
+    }
+
+    fn encoded_size(&self) -> usize {
+        0
+    }
+}
+
+impl<K, T, Key: StorageKey, InnerKey: StorageKey> StorableHint<Key> for VersionedMapping<K, T, InnerKey> {
+    type Type = VersionedMapping<K, T, Key>;
+    type PreferredKey = InnerKey;
+}
+
+impl<K, T, KeyType: StorageKey> StorageKey for VersionedMapping<K, T, KeyType> {
+    const KEY: Key = KeyType::KEY;
+}
+
+#[cfg(feature = "std")]
+impl<K, T, KeyType> ink::storage::traits::StorageLayout for VersionedMapping<K, T, KeyType>
+where
+    K: scale_info::TypeInfo + 'static,
+    KeyType: StorageKey + scale_info::TypeInfo + 'static,
+{
+    fn layout(key: &Key) -> ink::metadata::layout::Layout {
+        <Mapping<K, VersionedValue, KeyType> as ink::storage::traits::StorageLayout>::layout(key)
+    }
+}
+
+#[ink::event]
+pub struct Upgraded {
+    #[ink(topic)]
+    pub code_hash: Hash,
+    pub storage_version: u32,
+}
+
+#[ink::event]
+pub struct StorageMigrated {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct RecordV1 {
+        id: u32,
+    }
+
+    impl Migrate for RecordV1 {
+        const VERSION: u16 = 1;
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq)]
+    struct RecordV2 {
+        id: u32,
+        label: Option<u8>,
+    }
+
+    impl Migrate for RecordV2 {
+        const VERSION: u16 = 2;
+
+        fn migrate(version: u16, payload: &[u8]) -> Option<Self> {
+            match version {
+                1 => <RecordV1 as Decode>::decode(&mut &payload[..])
+                    .ok()
+                    .map(|old| RecordV2 { id: old.id, label: None }),
+                _ => None,
+            }
+        }
+    }
+
+    #[ink::test]
+    fn old_layouts_are_migrated_on_read() {
+        let mut v1: VersionedMapping<u32, RecordV1> = VersionedMapping::default();
+        v1.insert(7, &RecordV1 { id: 7 });
+
+        // Same storage key, read back by code that knows the newer layout.
+        let mut v2: VersionedMapping<u32, RecordV2> = VersionedMapping::default();
+        assert_eq!(v2.get(7), Some(RecordV2 { id: 7, label: None }));
+
+        v2.insert(7, &RecordV2 { id: 7, label: Some(1) });
+        assert_eq!(v2.get(7), Some(RecordV2 { id: 7, label: Some(1) }));
+        assert_eq!(VersionedValue::new(&RecordV2 { id: 7, label: None }).version, 2);
+    }
+
+    #[ink::test]
+    fn unknown_versions_are_rejected() {
+        let stored = VersionedValue {
+            version: 9,
+            payload: Encode::encode(&RecordV1 { id: 1 }),
+        };
+        assert_eq!(stored.decode::<RecordV2>(), None);
+        assert_eq!(stored.decode::<RecordV1>(), None);
+    }
+}
\ No newline at end of file
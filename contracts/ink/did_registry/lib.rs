@@ -1,11 +1,16 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
 #[ink::contract]
-mod did_registry {
+pub mod did_registry {
+    use axi_common::{
+        AccessControl, Migrate, Ownable, PauseScope, Pausable, Role, StorageMigrated, Upgraded, VersionedMapping,
+    };
     use ink::prelude::{string::String, vec::Vec};
-    use ink::storage::Mapping;
+    use ink::storage::{Lazy, Mapping};
     use scale::{Decode, Encode};
 
+    const STORAGE_VERSION: u32 = 1;
+
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
     #[cfg_attr(
         feature = "std",
@@ -22,6 +27,10 @@ mod did_registry {
         pub entity_type: EntityType,
     }
 
+    impl Migrate for DidDocument {
+        const VERSION: u16 = 1;
+    }
+
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
     #[cfg_attr(
         feature = "std",
@@ -86,12 +95,17 @@ mod did_registry {
 
     #[ink(storage)]
     pub struct DidRegistry {
-        dids: Mapping<String, DidDocument>,
+        dids: VersionedMapping<String, DidDocument>,
         vehicle_metadata: Mapping<String, VehicleMetadata>,
         sensor_metadata: Mapping<String, SensorMetadata>,
         owner_dids: Mapping<AccountId, Vec<String>>,
         authorizations: Mapping<String, Vec<AccountId>>,
-        owner: AccountId,
+        ownership: Ownable,
+        access: AccessControl,
+        pausable: Lazy<Pausable>,
+        /// Layout version of the data in storage. New root-level state has to live in
+        /// its own `Mapping`/`Lazy` cell so this struct keeps decoding across upgrades.
+        storage_version: u32,
         total_dids: u32,
     }
 
@@ -141,12 +155,15 @@ mod did_registry {
         #[ink(constructor)]
         pub fn new() -> Self {
             Self {
-                dids: Mapping::default(),
+                dids: VersionedMapping::default(),
                 vehicle_metadata: Mapping::default(),
                 sensor_metadata: Mapping::default(),
                 owner_dids: Mapping::default(),
                 authorizations: Mapping::default(),
-                owner: Self::env().caller(),
+                ownership: Ownable::new(Self::env().caller()),
+                access: AccessControl::with_all_roles(Self::env().caller()),
+                pausable: Lazy::new(),
+                storage_version: STORAGE_VERSION,
                 total_dids: 0,
             }
         }
@@ -159,6 +176,10 @@ mod did_registry {
             service_endpoint: String,
             metadata: VehicleMetadata,
         ) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Registration) {
+                return Err(String::from("Registration paused"));
+            }
+
             if !self.is_valid_did_format(&did_id) {
                 return Err(String::from("Invalid DID format"));
             }
@@ -207,6 +228,10 @@ mod did_registry {
             service_endpoint: String,
             metadata: SensorMetadata,
         ) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Registration) {
+                return Err(String::from("Registration paused"));
+            }
+
             if !self.is_valid_did_format(&did_id) {
                 return Err(String::from("Invalid DID format"));
             }
@@ -260,6 +285,10 @@ mod did_registry {
             public_key: [u8; 32],
             service_endpoint: String,
         ) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
             let mut did_doc = self.dids.get(&did_id).ok_or("DID not found")?;
             
             let caller = self.env().caller();
@@ -287,10 +316,14 @@ mod did_registry {
 
         #[ink(message)]
         pub fn revoke_did(&mut self, did_id: String, reason: String) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
             let mut did_doc = self.dids.get(&did_id).ok_or("DID not found")?;
             
             let caller = self.env().caller();
-            if did_doc.controller != caller && caller != self.owner {
+            if did_doc.controller != caller && !self.access.has_role(Role::Registrar, caller) {
                 return Err(String::from("Unauthorized"));
             }
 
@@ -314,6 +347,10 @@ mod did_registry {
             did_id: String,
             account: AccountId,
         ) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
             let did_doc = self.dids.get(&did_id).ok_or("DID not found")?;
             
             let caller = self.env().caller();
@@ -342,6 +379,10 @@ mod did_registry {
             did_id: String,
             account: AccountId,
         ) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
             let did_doc = self.dids.get(&did_id).ok_or("DID not found")?;
             
             let caller = self.env().caller();
@@ -372,8 +413,10 @@ mod did_registry {
         }
 
         #[ink(message)]
-        pub fn get_owner_dids(&self, owner: AccountId) -> Vec<String> {
-            self.owner_dids.get(owner).unwrap_or_default()
+        pub fn get_owner_dids(&self, owner: AccountId) -> Result<Vec<String>, String> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.owner_dids.get(owner).unwrap_or_default())
         }
 
         #[ink(message)]
@@ -400,8 +443,172 @@ mod did_registry {
         }
 
         #[ink(message)]
-        pub fn get_authorizations(&self, did_id: String) -> Vec<AccountId> {
-            self.authorizations.get(&did_id).unwrap_or_default()
+        pub fn get_authorizations(&self, did_id: String) -> Result<Vec<AccountId>, String> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.authorizations.get(&did_id).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_owner(&self) -> Option<AccountId> {
+            self.ownership.owner()
+        }
+
+        #[ink(message)]
+        pub fn get_pending_owner(&self) -> Option<AccountId> {
+            self.ownership.pending_owner()
+        }
+
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), String> {
+            self.ownership
+                .transfer_ownership(self.env().caller(), new_owner)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        /// Completes a pending ownership transfer; the roles held by the previous owner move with it.
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<(), String> {
+            let caller = self.env().caller();
+            let previous_owner = self
+                .ownership
+                .accept_ownership(caller)
+                .map_err(|_| String::from("Unauthorized"))?;
+            if let Some(previous_owner) = previous_owner {
+                self.access.transfer_all_roles(previous_owner, Some(caller));
+            }
+            Ok(())
+        }
+
+        /// Leaves the contract without an owner and drops every role the owner held.
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<(), String> {
+            let caller = self.env().caller();
+            self.ownership
+                .renounce_ownership(caller)
+                .map_err(|_| String::from("Unauthorized"))?;
+            self.access.transfer_all_roles(caller, None);
+            Ok(())
+        }
+
+        /// Guardians may pause; lifting a pause is reserved for admins.
+        #[ink(message)]
+        pub fn pause(&mut self, scope: PauseScope) -> Result<(), String> {
+            let caller = self.env().caller();
+            if !self.access.has_role(Role::Guardian, caller) && !self.access.has_role(Role::Admin, caller) {
+                return Err(String::from("Unauthorized"));
+            }
+            let mut pausable = self.pausable.get_or_default();
+            pausable.pause(scope, caller);
+            self.pausable.set(&pausable);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unpause(&mut self, scope: PauseScope) -> Result<(), String> {
+            let caller = self.env().caller();
+            if !self.access.has_role(Role::Admin, caller) {
+                return Err(String::from("Unauthorized"));
+            }
+            let mut pausable = self.pausable.get_or_default();
+            pausable.unpause(scope, caller);
+            self.pausable.set(&pausable);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self, scope: PauseScope) -> bool {
+            self.pausable.get_or_default().is_paused(scope)
+        }
+
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), String> {
+            if !self.access.has_role(Role::Admin, self.env().caller()) {
+                return Err(String::from("Unauthorized"));
+            }
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| String::from("Upgrade failed"))?;
+
+            self.env().emit_event(Upgraded {
+                code_hash,
+                storage_version: self.storage_version,
+            });
+            Ok(())
+        }
+
+        /// Run by the new code after `upgrade`. Records are migrated lazily as they are
+        /// read; eager steps for a version bump go here.
+        #[ink(message)]
+        pub fn migrate_storage(&mut self) -> Result<(), String> {
+            if !self.access.has_role(Role::Admin, self.env().caller()) {
+                return Err(String::from("Unauthorized"));
+            }
+            if self.storage_version >= STORAGE_VERSION {
+                return Err(String::from("Storage up to date"));
+            }
+
+            let from_version = self.storage_version;
+            self.storage_version = STORAGE_VERSION;
+
+            self.env().emit_event(StorageMigrated {
+                from_version,
+                to_version: STORAGE_VERSION,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version
+        }
+
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<(), String> {
+            self.access
+                .grant_role(self.env().caller(), role, account)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<(), String> {
+            self.access
+                .revoke_role(self.env().caller(), role, account)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        #[ink(message)]
+        pub fn renounce_role(&mut self, role: Role) -> Result<(), String> {
+            self.access
+                .renounce_role(self.env().caller(), role)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        #[ink(message)]
+        pub fn set_role_admin(&mut self, role: Role, admin_role: Role) -> Result<(), String> {
+            self.access
+                .set_role_admin(self.env().caller(), role, admin_role)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.access.has_role(role, account)
+        }
+
+        #[ink(message)]
+        pub fn get_role_admin(&self, role: Role) -> Role {
+            self.access.role_admin(role)
+        }
+
+        /// Auditors keep read access while list queries are paused.
+        fn ensure_queries_allowed(&self) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Query)
+                && !self.access.has_role(Role::Auditor, self.env().caller())
+            {
+                return Err(String::from("Queries paused"));
+            }
+            Ok(())
         }
 
         fn is_valid_did_format(&self, did_id: &str) -> bool {
@@ -581,5 +788,154 @@ mod did_registry {
             assert!(result.is_err());
             assert_eq!(result.unwrap_err(), "Invalid DID format");
         }
+
+        #[ink::test]
+        fn registrar_can_revoke_any_did() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = DidRegistry::new();
+            let did_id = String::from("did:peaq:vehicle:test001");
+
+            let metadata = VehicleMetadata {
+                vin: String::from("TEST123456789"),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from("AXI001"),
+                engine_type: EngineType::Electric,
+            };
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.create_vehicle_did(
+                did_id.clone(),
+                [1u8; 32],
+                String::from("https://api.aximobility.com/vehicle/test001"),
+                metadata,
+            ).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            let result = registry.revoke_did(did_id.clone(), String::from("compromised"));
+            assert_eq!(result.unwrap_err(), "Unauthorized");
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            registry.grant_role(Role::Registrar, accounts.charlie).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            registry.revoke_did(did_id.clone(), String::from("compromised")).unwrap();
+            assert!(!registry.is_did_active(did_id));
+        }
+
+        #[ink::test]
+        fn ownership_transfer_requires_acceptance() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = DidRegistry::new();
+
+            registry.transfer_ownership(accounts.bob).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.accept_ownership().unwrap_err(), "Unauthorized");
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.accept_ownership().unwrap();
+            assert_eq!(registry.get_owner(), Some(accounts.bob));
+            assert!(registry.has_role(Role::Registrar, accounts.bob));
+            assert!(!registry.has_role(Role::Registrar, accounts.alice));
+        }
+
+        #[derive(Encode, Decode, Debug, PartialEq)]
+        struct DidDocumentV2 {
+            document: DidDocument,
+            also_known_as: Vec<String>,
+        }
+
+        impl Migrate for DidDocumentV2 {
+            const VERSION: u16 = 2;
+
+            fn migrate(version: u16, payload: &[u8]) -> Option<Self> {
+                match version {
+                    1 => DidDocument::decode(&mut &payload[..]).ok().map(|document| DidDocumentV2 {
+                        document,
+                        also_known_as: Vec::new(),
+                    }),
+                    _ => None,
+                }
+            }
+        }
+
+        #[ink::test]
+        fn stored_did_documents_migrate_to_new_layout() {
+            use axi_common::VersionedValue;
+            use ink::storage::traits::StorageKey;
+
+            let mut registry = DidRegistry::new();
+            let did_id = String::from("did:peaq:vehicle:test001");
+
+            let metadata = VehicleMetadata {
+                vin: String::from("TEST123456789"),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from("AXI001"),
+                engine_type: EngineType::Electric,
+            };
+
+            registry.create_vehicle_did(
+                did_id.clone(),
+                [1u8; 32],
+                String::from("https://api.aximobility.com/vehicle/test001"),
+                metadata,
+            ).unwrap();
+
+            let entry = (registry.dids.key(), did_id.clone());
+            let stored: VersionedValue = ink::env::get_contract_storage(&entry).unwrap().unwrap();
+            let migrated = stored.decode::<DidDocumentV2>().unwrap();
+            assert_eq!(Some(migrated.document), registry.get_did(did_id));
+            assert!(migrated.also_known_as.is_empty());
+
+            assert_eq!(registry.migrate_storage().unwrap_err(), "Storage up to date");
+            registry.storage_version = 0;
+            registry.migrate_storage().unwrap();
+            assert_eq!(registry.get_storage_version(), STORAGE_VERSION);
+        }
+
+        #[ink::test]
+        fn paused_registration_rejects_new_dids() {
+            let mut registry = DidRegistry::new();
+            registry.pause(PauseScope::Registration).unwrap();
+
+            let metadata = VehicleMetadata {
+                vin: String::from("TEST123456789"),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from("AXI001"),
+                engine_type: EngineType::Electric,
+            };
+
+            let result = registry.create_vehicle_did(
+                String::from("did:peaq:vehicle:test001"),
+                [1u8; 32],
+                String::from("https://api.aximobility.com/vehicle/test001"),
+                metadata,
+            );
+            assert_eq!(result.unwrap_err(), "Registration paused");
+            assert!(!registry.is_paused(PauseScope::Mutation));
+        }
+
+        #[ink::test]
+        fn paused_queries_return_an_error() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = DidRegistry::new();
+            registry.grant_role(Role::Auditor, accounts.charlie).unwrap();
+            registry.pause(PauseScope::Query).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.get_owner_dids(accounts.alice).unwrap_err(), "Queries paused");
+            assert_eq!(
+                registry.get_authorizations(String::from("did:peaq:vehicle:test001")).unwrap_err(),
+                "Queries paused"
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.get_owner_dids(accounts.alice), Ok(Vec::new()));
+        }
     }
 }
\ No newline at end of file
@@ -4,9 +4,16 @@
 
 #[ink::contract]
 mod telemetry_processor {
+    use axi_common::{
+        AccessControl, Migrate, Ownable, PauseScope, Pausable, Role, StorageMigrated, Upgraded, VersionedMapping,
+    };
+    use ink::codegen::TraitCallBuilder;
     use ink::prelude::string::String;
-    use ink::storage::Mapping;
+    use ink::storage::{Lazy, Mapping};
     use scale::{Decode, Encode};
+    use vehicle_registry::vehicle_registry::{VehicleRegistryRef, VehicleStatus};
+
+    const STORAGE_VERSION: u32 = 1;
 
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default)]
     #[cfg_attr(
@@ -36,6 +43,10 @@ mod telemetry_processor {
         pub validated: bool,
     }
 
+    impl Migrate for TelemetryRecord {
+        const VERSION: u16 = 1;
+    }
+
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
     #[cfg_attr(
         feature = "std",
@@ -65,12 +76,19 @@ mod telemetry_processor {
     #[ink(storage)]
     pub struct TelemetryProcessor {
         stats: ProcessingStats,
-        owner: AccountId,
+        ownership: Ownable,
+        access: AccessControl,
+        pausable: Lazy<Pausable>,
+        /// Layout version of the data in storage. New root-level state has to live in
+        /// its own `Mapping`/`Lazy` cell so this struct keeps decoding across upgrades.
+        storage_version: u32,
         processing_enabled: bool,
         authorized_vehicles: Mapping<[u8; 32], bool>,
-        records: Mapping<[u8; 32], TelemetryRecord>,
+        records: VersionedMapping<[u8; 32], TelemetryRecord>,
         batches: Mapping<[u8; 32], BatchRecord>,
         vehicle_counters: Mapping<[u8; 32], u32>,
+        vehicle_registry: Lazy<Option<AccountId>>,
+        vehicle_ids: Mapping<[u8; 32], String>,
     }
 
     #[ink(event)]
@@ -120,12 +138,17 @@ mod telemetry_processor {
         pub fn new() -> Self {
             Self {
                 stats: ProcessingStats::default(),
-                owner: Self::env().caller(),
+                ownership: Ownable::new(Self::env().caller()),
+                access: AccessControl::with_all_roles(Self::env().caller()),
+                pausable: Lazy::new(),
+                storage_version: STORAGE_VERSION,
                 processing_enabled: true,
                 authorized_vehicles: Mapping::default(),
-                records: Mapping::default(),
+                records: VersionedMapping::default(),
                 batches: Mapping::default(),
                 vehicle_counters: Mapping::default(),
+                vehicle_registry: Lazy::new(),
+                vehicle_ids: Mapping::default(),
             }
         }
 
@@ -137,6 +160,10 @@ mod telemetry_processor {
             data_hash: [u8; 32],
             quality_score: u8,
         ) -> Result<[u8; 32], String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
             if !self.processing_enabled {
                 return Err(String::from("Processing disabled"));
             }
@@ -145,6 +172,8 @@ mod telemetry_processor {
                 return Err(String::from("Vehicle not authorized"));
             }
 
+            self.ensure_vehicle_in_service(vehicle_hash)?;
+
             if quality_score < 50 {
                 return Err(String::from("Quality score too low"));
             }
@@ -223,6 +252,10 @@ mod telemetry_processor {
             )>,
             merkle_root: [u8; 32],
         ) -> Result<u16, String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
             if !self.processing_enabled {
                 return Err(String::from("Processing disabled"));
             }
@@ -255,6 +288,10 @@ mod telemetry_processor {
                     continue;
                 }
 
+                if self.ensure_vehicle_in_service(vehicle_hash).is_err() {
+                    continue;
+                }
+
                 if quality_score < 50 {
                     continue;
                 }
@@ -326,7 +363,11 @@ mod telemetry_processor {
 
         #[ink(message)]
         pub fn authorize_vehicle(&mut self, vehicle_hash: [u8; 32]) -> Result<(), String> {
-            if self.env().caller() != self.owner {
+            if self.pausable.get_or_default().is_paused(PauseScope::Registration) {
+                return Err(String::from("Registration paused"));
+            }
+
+            if !self.access.has_role(Role::Registrar, self.env().caller()) {
                 return Err(String::from("Unauthorized"));
             }
             
@@ -342,7 +383,11 @@ mod telemetry_processor {
 
         #[ink(message)]
         pub fn deauthorize_vehicle(&mut self, vehicle_hash: [u8; 32]) -> Result<(), String> {
-            if self.env().caller() != self.owner {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
+            if !self.access.has_role(Role::Registrar, self.env().caller()) {
                 return Err(String::from("Unauthorized"));
             }
             
@@ -352,7 +397,11 @@ mod telemetry_processor {
 
         #[ink(message)]
         pub fn validate_record(&mut self, record_id: [u8; 32]) -> Result<(), String> {
-            if self.env().caller() != self.owner {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
+            if !self.access.has_role(Role::TelemetryValidator, self.env().caller()) {
                 return Err(String::from("Unauthorized"));
             }
 
@@ -373,13 +422,55 @@ mod telemetry_processor {
             self.authorized_vehicles.get(vehicle_hash).unwrap_or(false)
         }
 
+        /// Once set, records for a vehicle linked with `set_vehicle_id` are refused while
+        /// that vehicle is suspended or deregistered in this `VehicleRegistry` contract.
         #[ink(message)]
-        pub fn toggle_processing(&mut self) -> Result<(), String> {
-            if self.env().caller() != self.owner {
+        pub fn set_vehicle_registry(&mut self, vehicle_registry: Option<AccountId>) -> Result<(), String> {
+            if !self.access.has_role(Role::Admin, self.env().caller()) {
+                return Err(String::from("Unauthorized"));
+            }
+
+            self.vehicle_registry.set(&vehicle_registry);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_registry(&self) -> Option<AccountId> {
+            self.vehicle_registry.get().flatten()
+        }
+
+        #[ink(message)]
+        pub fn set_vehicle_id(&mut self, vehicle_hash: [u8; 32], vehicle_id: Option<String>) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Mutation) {
+                return Err(String::from("Mutation paused"));
+            }
+
+            if !self.access.has_role(Role::Registrar, self.env().caller()) {
+                return Err(String::from("Unauthorized"));
+            }
+
+            match vehicle_id {
+                Some(vehicle_id) => self.vehicle_ids.insert(vehicle_hash, &vehicle_id),
+                None => {
+                    self.vehicle_ids.remove(vehicle_hash);
+                    None
+                }
+            };
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_id(&self, vehicle_hash: [u8; 32]) -> Option<String> {
+            self.vehicle_ids.get(vehicle_hash)
+        }
+
+        #[ink(message)]
+        pub fn set_processing_enabled(&mut self, enabled: bool) -> Result<(), String> {
+            if !self.access.has_role(Role::Admin, self.env().caller()) {
                 return Err(String::from("Unauthorized"));
             }
             
-            self.processing_enabled = !self.processing_enabled;
+            self.processing_enabled = enabled;
             Ok(())
         }
 
@@ -389,13 +480,17 @@ mod telemetry_processor {
         }
 
         #[ink(message)]
-        pub fn get_record(&self, record_id: [u8; 32]) -> Option<TelemetryRecord> {
-            self.records.get(record_id)
+        pub fn get_record(&self, record_id: [u8; 32]) -> Result<Option<TelemetryRecord>, String> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.records.get(record_id))
         }
 
         #[ink(message)]
-        pub fn get_batch(&self, batch_id: [u8; 32]) -> Option<BatchRecord> {
-            self.batches.get(batch_id)
+        pub fn get_batch(&self, batch_id: [u8; 32]) -> Result<Option<BatchRecord>, String> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.batches.get(batch_id))
         }
 
         #[ink(message)]
@@ -409,8 +504,186 @@ mod telemetry_processor {
         }
 
         #[ink(message)]
-        pub fn get_owner(&self) -> AccountId {
-            self.owner
+        pub fn get_owner(&self) -> Option<AccountId> {
+            self.ownership.owner()
+        }
+
+        #[ink(message)]
+        pub fn get_pending_owner(&self) -> Option<AccountId> {
+            self.ownership.pending_owner()
+        }
+
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), String> {
+            self.ownership
+                .transfer_ownership(self.env().caller(), new_owner)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        /// Completes a pending ownership transfer; the roles held by the previous owner move with it.
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<(), String> {
+            let caller = self.env().caller();
+            let previous_owner = self
+                .ownership
+                .accept_ownership(caller)
+                .map_err(|_| String::from("Unauthorized"))?;
+            if let Some(previous_owner) = previous_owner {
+                self.access.transfer_all_roles(previous_owner, Some(caller));
+            }
+            Ok(())
+        }
+
+        /// Leaves the contract without an owner and drops every role the owner held.
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<(), String> {
+            let caller = self.env().caller();
+            self.ownership
+                .renounce_ownership(caller)
+                .map_err(|_| String::from("Unauthorized"))?;
+            self.access.transfer_all_roles(caller, None);
+            Ok(())
+        }
+
+        /// Guardians may pause; lifting a pause is reserved for admins.
+        #[ink(message)]
+        pub fn pause(&mut self, scope: PauseScope) -> Result<(), String> {
+            let caller = self.env().caller();
+            if !self.access.has_role(Role::Guardian, caller) && !self.access.has_role(Role::Admin, caller) {
+                return Err(String::from("Unauthorized"));
+            }
+            let mut pausable = self.pausable.get_or_default();
+            pausable.pause(scope, caller);
+            self.pausable.set(&pausable);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unpause(&mut self, scope: PauseScope) -> Result<(), String> {
+            let caller = self.env().caller();
+            if !self.access.has_role(Role::Admin, caller) {
+                return Err(String::from("Unauthorized"));
+            }
+            let mut pausable = self.pausable.get_or_default();
+            pausable.unpause(scope, caller);
+            self.pausable.set(&pausable);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self, scope: PauseScope) -> bool {
+            self.pausable.get_or_default().is_paused(scope)
+        }
+
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), String> {
+            if !self.access.has_role(Role::Admin, self.env().caller()) {
+                return Err(String::from("Unauthorized"));
+            }
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| String::from("Upgrade failed"))?;
+
+            self.env().emit_event(Upgraded {
+                code_hash,
+                storage_version: self.storage_version,
+            });
+            Ok(())
+        }
+
+        /// Run by the new code after `upgrade`. Records are migrated lazily as they are
+        /// read; eager steps for a version bump go here.
+        #[ink(message)]
+        pub fn migrate_storage(&mut self) -> Result<(), String> {
+            if !self.access.has_role(Role::Admin, self.env().caller()) {
+                return Err(String::from("Unauthorized"));
+            }
+            if self.storage_version >= STORAGE_VERSION {
+                return Err(String::from("Storage up to date"));
+            }
+
+            let from_version = self.storage_version;
+            self.storage_version = STORAGE_VERSION;
+
+            self.env().emit_event(StorageMigrated {
+                from_version,
+                to_version: STORAGE_VERSION,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version
+        }
+
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<(), String> {
+            self.access
+                .grant_role(self.env().caller(), role, account)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<(), String> {
+            self.access
+                .revoke_role(self.env().caller(), role, account)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        #[ink(message)]
+        pub fn renounce_role(&mut self, role: Role) -> Result<(), String> {
+            self.access
+                .renounce_role(self.env().caller(), role)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        #[ink(message)]
+        pub fn set_role_admin(&mut self, role: Role, admin_role: Role) -> Result<(), String> {
+            self.access
+                .set_role_admin(self.env().caller(), role, admin_role)
+                .map_err(|_| String::from("Unauthorized"))
+        }
+
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.access.has_role(role, account)
+        }
+
+        #[ink(message)]
+        pub fn get_role_admin(&self, role: Role) -> Role {
+            self.access.role_admin(role)
+        }
+
+        /// Auditors keep read access to telemetry history while queries are paused.
+        fn ensure_queries_allowed(&self) -> Result<(), String> {
+            if self.pausable.get_or_default().is_paused(PauseScope::Query)
+                && !self.access.has_role(Role::Auditor, self.env().caller())
+            {
+                return Err(String::from("Queries paused"));
+            }
+            Ok(())
+        }
+
+        fn ensure_vehicle_in_service(&self, vehicle_hash: [u8; 32]) -> Result<(), String> {
+            let (Some(address), Some(vehicle_id)) = (self.get_vehicle_registry(), self.vehicle_ids.get(vehicle_hash))
+            else {
+                return Ok(());
+            };
+            let vehicle_registry: VehicleRegistryRef = ink::env::call::FromAccountId::from_account_id(address);
+
+            let vehicle = vehicle_registry
+                .call()
+                .get_vehicle(vehicle_id)
+                .try_invoke()
+                .map_err(|_| String::from("Vehicle registry call failed"))?
+                .map_err(|_| String::from("Vehicle registry call failed"))?
+                .ok_or(String::from("Vehicle not registered"))?;
+
+            if matches!(vehicle.status, VehicleStatus::Suspended | VehicleStatus::Deregistered) {
+                return Err(String::from("Vehicle not in service"));
+            }
+            Ok(())
         }
     }
 
@@ -492,5 +765,236 @@ mod telemetry_processor {
             assert!(result.is_err());
             assert_eq!(result.unwrap_err(), "Quality score too low");
         }
+
+        #[ink::test]
+        fn validation_requires_validator_role() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut processor = TelemetryProcessor::new();
+            let vehicle_hash = [1u8; 32];
+
+            processor.authorize_vehicle(vehicle_hash).unwrap();
+            let record_id = processor.process_record(vehicle_hash, 1, [2u8; 32], 80).unwrap();
+            processor.grant_role(Role::TelemetryValidator, accounts.bob).unwrap();
+            processor.revoke_role(Role::TelemetryValidator, accounts.alice).unwrap();
+
+            let result = processor.validate_record(record_id);
+            assert_eq!(result.unwrap_err(), "Unauthorized");
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            processor.validate_record(record_id).unwrap();
+            assert!(processor.get_record(record_id).unwrap().unwrap().validated);
+            assert_eq!(processor.authorize_vehicle([9u8; 32]).unwrap_err(), "Unauthorized");
+        }
+
+        #[ink::test]
+        fn renounced_ownership_drops_admin_rights() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut processor = TelemetryProcessor::new();
+
+            processor.transfer_ownership(accounts.bob).unwrap();
+            processor.renounce_ownership().unwrap();
+            assert_eq!(processor.get_owner(), None);
+            assert_eq!(processor.get_pending_owner(), None);
+            assert_eq!(processor.set_processing_enabled(false).unwrap_err(), "Unauthorized");
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(processor.accept_ownership().unwrap_err(), "Unauthorized");
+        }
+
+        #[derive(Encode, Decode, Debug, PartialEq)]
+        struct TelemetryRecordV2 {
+            record: TelemetryRecord,
+            batch_id: Option<[u8; 32]>,
+        }
+
+        impl Migrate for TelemetryRecordV2 {
+            const VERSION: u16 = 2;
+
+            fn migrate(version: u16, payload: &[u8]) -> Option<Self> {
+                match version {
+                    1 => TelemetryRecord::decode(&mut &payload[..]).ok().map(|record| TelemetryRecordV2 {
+                        record,
+                        batch_id: None,
+                    }),
+                    _ => None,
+                }
+            }
+        }
+
+        #[ink::test]
+        fn stored_records_migrate_to_new_layout() {
+            use axi_common::VersionedValue;
+            use ink::storage::traits::StorageKey;
+
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut processor = TelemetryProcessor::new();
+            let vehicle_hash = [1u8; 32];
+
+            processor.authorize_vehicle(vehicle_hash).unwrap();
+            let record_id = processor.process_record(vehicle_hash, 1, [2u8; 32], 80).unwrap();
+
+            let entry = (processor.records.key(), record_id);
+            let stored: VersionedValue = ink::env::get_contract_storage(&entry).unwrap().unwrap();
+            let migrated = stored.decode::<TelemetryRecordV2>().unwrap();
+            assert_eq!(Ok(Some(migrated.record)), processor.get_record(record_id));
+            assert_eq!(migrated.batch_id, None);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(processor.upgrade(Hash::from([1u8; 32])).unwrap_err(), "Unauthorized");
+        }
+
+        #[ink::test]
+        fn processing_flag_is_set_explicitly() {
+            let mut processor = TelemetryProcessor::new();
+            let vehicle_hash = [1u8; 32];
+            processor.authorize_vehicle(vehicle_hash).unwrap();
+
+            processor.set_processing_enabled(false).unwrap();
+            processor.set_processing_enabled(false).unwrap();
+            assert!(!processor.is_processing_enabled());
+            let result = processor.process_record(vehicle_hash, 1, [2u8; 32], 80);
+            assert_eq!(result.unwrap_err(), "Processing disabled");
+
+            processor.set_processing_enabled(true).unwrap();
+            processor.pause(PauseScope::Mutation).unwrap();
+            let result = processor.process_record(vehicle_hash, 1, [2u8; 32], 80);
+            assert_eq!(result.unwrap_err(), "Mutation paused");
+        }
+
+        #[ink::test]
+        fn vehicle_links_are_role_gated() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut processor = TelemetryProcessor::new();
+            let vehicle_hash = [1u8; 32];
+
+            processor.set_vehicle_id(vehicle_hash, Some(String::from("AXI_001"))).unwrap();
+            assert_eq!(processor.get_vehicle_id(vehicle_hash), Some(String::from("AXI_001")));
+            // Without a registry configured the link is not consulted.
+            processor.authorize_vehicle(vehicle_hash).unwrap();
+            assert!(processor.process_record(vehicle_hash, 1, [2u8; 32], 80).is_ok());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(processor.set_vehicle_id(vehicle_hash, None).unwrap_err(), "Unauthorized");
+            assert_eq!(processor.set_vehicle_registry(Some(accounts.django)).unwrap_err(), "Unauthorized");
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            processor.set_vehicle_registry(Some(accounts.django)).unwrap();
+            assert_eq!(processor.get_vehicle_registry(), Some(accounts.django));
+            processor.set_vehicle_id(vehicle_hash, None).unwrap();
+            assert_eq!(processor.get_vehicle_id(vehicle_hash), None);
+        }
+
+        #[ink::test]
+        fn paused_queries_return_an_error() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut processor = TelemetryProcessor::new();
+            let vehicle_hash = [1u8; 32];
+            processor.authorize_vehicle(vehicle_hash).unwrap();
+            let record_id = processor.process_record(vehicle_hash, 1, [2u8; 32], 80).unwrap();
+            processor.grant_role(Role::Auditor, accounts.charlie).unwrap();
+            processor.pause(PauseScope::Query).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(processor.get_record(record_id).unwrap_err(), "Queries paused");
+            assert_eq!(processor.get_batch([3u8; 32]).unwrap_err(), "Queries paused");
+            assert_eq!(processor.get_stats().total_records, 1);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert!(processor.get_record(record_id).unwrap().is_some());
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::ContractsBackend;
+        use vehicle_registry::vehicle_registry::{
+            EngineType, StatusReason, VehicleRegistration, VehicleRegistry, VehicleRegistryRef,
+        };
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn suspended_vehicle_records_are_refused<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let mut registry_constructor = VehicleRegistryRef::new();
+            let registry = client
+                .instantiate("vehicle_registry", &ink_e2e::alice(), &mut registry_constructor)
+                .submit()
+                .await
+                .expect("vehicle_registry instantiate failed");
+            let mut registry_call = registry.call_builder::<VehicleRegistry>();
+
+            let mut constructor = TelemetryProcessorRef::new();
+            let contract = client
+                .instantiate("telemetry_processor", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("telemetry_processor instantiate failed");
+            let mut call = contract.call_builder::<TelemetryProcessor>();
+
+            let vehicle_id = String::from("AXI_001");
+            let vehicle_hash = [1u8; 32];
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &registry_call.register_vehicle(VehicleRegistration {
+                        vehicle_id: vehicle_id.clone(),
+                        did_identifier: String::from("did:peaq:vehicle:axi_001"),
+                        vin: String::from("WBA3E1EA0PF000001"),
+                        make: String::from("BMW"),
+                        model: String::from("i4"),
+                        year: 2023,
+                        license_plate: String::from("AXI001"),
+                        engine_type: EngineType::Electric,
+                        battery_capacity: Some(80),
+                    }),
+                )
+                .submit()
+                .await
+                .expect("register_vehicle failed");
+
+            client
+                .call(&ink_e2e::alice(), &call.set_vehicle_registry(Some(registry.account_id)))
+                .submit()
+                .await
+                .expect("set_vehicle_registry failed");
+            client
+                .call(&ink_e2e::alice(), &call.set_vehicle_id(vehicle_hash, Some(vehicle_id.clone())))
+                .submit()
+                .await
+                .expect("set_vehicle_id failed");
+            client
+                .call(&ink_e2e::alice(), &call.authorize_vehicle(vehicle_hash))
+                .submit()
+                .await
+                .expect("authorize_vehicle failed");
+
+            let result = client
+                .call(&ink_e2e::alice(), &call.process_record(vehicle_hash, 1, [2u8; 32], 80))
+                .dry_run()
+                .await?;
+            assert!(result.return_value().is_ok());
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &registry_call.update_vehicle_status(
+                        vehicle_id,
+                        VehicleStatus::Suspended,
+                        StatusReason::Theft,
+                    ),
+                )
+                .submit()
+                .await
+                .expect("update_vehicle_status failed");
+
+            let result = client
+                .call(&ink_e2e::alice(), &call.process_record(vehicle_hash, 1, [2u8; 32], 80))
+                .dry_run()
+                .await?;
+            assert_eq!(result.return_value().unwrap_err(), "Vehicle not in service");
+
+            Ok(())
+        }
     }
 }
\ No newline at end of file
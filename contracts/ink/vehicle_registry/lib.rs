@@ -1,10 +1,33 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
 #[ink::contract]
-mod vehicle_registry {
+pub mod vehicle_registry {
+    use axi_common::{
+        AccessControl, AccessControlError, Migrate, Ownable, OwnableError, PauseScope, Pausable, PausableError, Role,
+        StorageMigrated, Upgraded, VersionedMapping,
+    };
+    use did_registry::did_registry::{DidRegistryRef, DidStatus, EntityType};
+    use ink::codegen::TraitCallBuilder;
     use ink::prelude::{string::String, vec::Vec};
-    use ink::storage::Mapping;
-    use scale::{Decode, Encode};
+    use ink::storage::{traits::StorageKey, Lazy, Mapping};
+    use scale::{Decode, Encode, EncodeLike};
+
+    const STORAGE_VERSION: u32 = 1;
+    const MAX_PAGE_SIZE: u32 = 100;
+    const DEFAULT_MAX_BATCH_SIZE: u32 = 50;
+    const MAX_BATCH_SIZE_LIMIT: u32 = 500;
+    const MAX_VEHICLE_SCAN: u32 = 1_000;
+    const DEFAULT_LOCATION_HISTORY_CAPACITY: u32 = 100;
+    const MAX_LOCATION_HISTORY_CAPACITY: u32 = 1_000;
+    const MAX_LATITUDE: i32 = 90_000_000;
+    const MAX_LONGITUDE: i32 = 180_000_000;
+    const DEFAULT_MAX_SPEED_KMH: u32 = 300;
+    const MAX_GEOFENCE_VERTICES: usize = 32;
+    const MAX_GEOFENCES_PER_VEHICLE: usize = 16;
+    // Keeps a vehicle's sensor index within one page, so whole-vehicle walks see every sensor.
+    const MAX_SENSORS_PER_VEHICLE: u32 = MAX_PAGE_SIZE;
+    // Metres per degree of latitude, i.e. micrometres per micro-degree.
+    const MICROMETRES_PER_MICRODEGREE: i128 = 111_320;
 
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
     #[cfg_attr(
@@ -30,6 +53,10 @@ mod vehicle_registry {
         pub location: Option<Location>,
     }
 
+    impl Migrate for Vehicle {
+        const VERSION: u16 = 1;
+    }
+
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
     #[cfg_attr(
         feature = "std",
@@ -55,6 +82,98 @@ mod vehicle_registry {
         Deregistered,
     }
 
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum StatusReason {
+        Manual,
+        OwnerRequest,
+        Theft,
+        Accident,
+        Compliance,
+        EndOfLife,
+        ServiceStarted,
+        ServiceCompleted,
+        GeofenceViolation,
+        DidSuspended,
+        DidRevoked,
+        Reregistered,
+    }
+
+    impl VehicleStatus {
+        pub const ALL: [VehicleStatus; 5] = [
+            VehicleStatus::Active,
+            VehicleStatus::Inactive,
+            VehicleStatus::Maintenance,
+            VehicleStatus::Suspended,
+            VehicleStatus::Deregistered,
+        ];
+    }
+
+    impl EngineType {
+        pub const ALL: [EngineType; 4] = [
+            EngineType::Electric,
+            EngineType::Hybrid,
+            EngineType::Gasoline,
+            EngineType::Diesel,
+        ];
+    }
+
+    impl SensorType {
+        pub const ALL: [SensorType; 9] = [
+            SensorType::Gps,
+            SensorType::Accelerometer,
+            SensorType::Gyroscope,
+            SensorType::Battery,
+            SensorType::Temperature,
+            SensorType::Speed,
+            SensorType::FuelLevel,
+            SensorType::EngineRpm,
+            SensorType::Odometer,
+        ];
+    }
+
+    impl SensorStatus {
+        /// Every status but `Retired`: retired sensors keep their record but leave the stats.
+        pub const LIVE: [SensorStatus; 4] = [
+            SensorStatus::Active,
+            SensorStatus::Inactive,
+            SensorStatus::Faulty,
+            SensorStatus::Calibrating,
+        ];
+    }
+
+    impl StatusReason {
+        /// Reasons reserved for status changes the contract makes on its own.
+        pub fn is_automatic(&self) -> bool {
+            matches!(
+                self,
+                StatusReason::ServiceStarted
+                    | StatusReason::ServiceCompleted
+                    | StatusReason::GeofenceViolation
+                    | StatusReason::DidSuspended
+                    | StatusReason::DidRevoked
+                    | StatusReason::Reregistered
+            )
+        }
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct StatusRecord {
+        pub from: VehicleStatus,
+        pub to: VehicleStatus,
+        pub reason: StatusReason,
+        pub changed_by: AccountId,
+        pub timestamp: u64,
+    }
+
+    impl Migrate for StatusRecord {
+        const VERSION: u16 = 1;
+    }
+
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
     #[cfg_attr(
         feature = "std",
@@ -66,6 +185,70 @@ mod vehicle_registry {
         pub timestamp: u64,
     }
 
+    impl Migrate for Location {
+        const VERSION: u16 = 1;
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct LocationBuffer {
+        pub capacity: u32,
+        pub head: u32,
+        pub len: u32,
+    }
+
+    impl Migrate for LocationBuffer {
+        const VERSION: u16 = 1;
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum GeofenceShape {
+        Circle {
+            latitude: i32,
+            longitude: i32,
+            radius_m: u32,
+        },
+        Polygon {
+            // (latitude, longitude) pairs, same scale as `Location`
+            vertices: Vec<(i32, i32)>,
+        },
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum GeofenceKind {
+        Inclusion, // vehicle must stay inside
+        Exclusion, // vehicle must stay outside
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Geofence {
+        pub id: u32,
+        pub owner: AccountId,
+        pub shape: GeofenceShape,
+        pub kind: GeofenceKind,
+        pub suspend_on_violation: bool,
+        pub created_at: u64,
+    }
+
+    impl Migrate for Geofence {
+        const VERSION: u16 = 1;
+    }
+
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
     #[cfg_attr(
         feature = "std",
@@ -101,6 +284,10 @@ mod vehicle_registry {
         pub accuracy: String,
     }
 
+    impl Migrate for Sensor {
+        const VERSION: u16 = 1;
+    }
+
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
     #[cfg_attr(
         feature = "std",
@@ -130,6 +317,7 @@ mod vehicle_registry {
         Speed,
         FuelLevel,
         EngineRpm,
+        Odometer,
     }
 
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
@@ -142,33 +330,442 @@ mod vehicle_registry {
         Inactive,
         Faulty,
         Calibrating,
+        Retired,
+    }
+
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum VinRegion {
+        NorthAmerica,
+        SouthAmerica,
+        Europe,
+        Asia,
+        Africa,
+        Oceania,
+    }
+
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum BatchMode {
+        Atomic,
+        BestEffort,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct VehicleFilter {
+        pub status: Option<VehicleStatus>,
+        pub engine_type: Option<EngineType>,
+        pub make: Option<String>,
+        pub model: Option<String>,
+        pub min_year: Option<u16>,
+        pub max_year: Option<u16>,
     }
 
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct VehiclePage {
+        pub vehicles: Vec<Vehicle>,
+        /// Position to resume from, `None` once the whole index has been scanned.
+        pub next_cursor: Option<u32>,
+    }
+
+    /// Stored inline in the contract root, so its layout is frozen; the per-status and
+    /// per-type breakdowns live in their own mappings.
     #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default)]
     #[cfg_attr(
         feature = "std",
         derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
     )]
+    struct StatsTotals {
+        total_vehicles: u32,
+        active_vehicles: u32,
+        total_sensors: u32,
+        active_sensors: u32,
+        electric_vehicles: u32,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub struct RegistryStats {
         pub total_vehicles: u32,
         pub active_vehicles: u32,
         pub total_sensors: u32,
         pub active_sensors: u32,
         pub electric_vehicles: u32,
+        pub vehicles_by_status: Vec<(VehicleStatus, u32)>,
+        pub vehicles_by_engine_type: Vec<(EngineType, u32)>,
+        pub sensors_by_type: Vec<(SensorType, u32)>,
+        pub sensors_by_status: Vec<(SensorStatus, u32)>,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct PendingTransfer {
+        pub new_owner: AccountId,
+        pub keep_operator: bool,
+        pub proposed_at: u64,
+    }
+
+    impl Migrate for PendingTransfer {
+        const VERSION: u16 = 1;
+    }
+
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct OperatorPermissions {
+        pub can_update_location: bool,
+        pub can_change_status: bool,
+        pub can_register_sensors: bool,
+        pub can_update_mileage: bool,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct OperatorAssignment {
+        pub operator: AccountId,
+        pub permissions: OperatorPermissions,
+        pub assigned_at: u64,
+        pub expires_at: Option<u64>,
+    }
+
+    impl Migrate for OperatorAssignment {
+        const VERSION: u16 = 1;
+    }
+
+    #[derive(Clone, Copy)]
+    enum OperatorAction {
+        UpdateLocation,
+        ChangeStatus,
+        ManageSensors,
+        UpdateMileage,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum StatusActor {
+        Owner,
+        Operator,
+        FleetManager,
+        Registrar,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct OwnershipRecord {
+        pub owner: AccountId,
+        pub acquired_at: u64,
+        pub released_at: u64,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct PlateRecord {
+        pub plate: String,
+        pub assigned_at: u64,
+        pub released_at: Option<u64>,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct PlateAssignment {
+        pub vehicle_id: String,
+        pub assigned_at: u64,
+        pub released_at: Option<u64>,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CalibrationRecord {
+        pub certificate_hash: [u8; 32],
+        pub technician: AccountId,
+        pub calibrated_at: u64,
+        pub recorded_by: AccountId,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct InstallationRecord {
+        pub vehicle_id: String,
+        pub installed_at: u64,
+        pub removed_at: Option<u64>,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct MileageAttestation {
+        pub sensor_id: String,
+        // sr25519 signature over SCALE(contract, vehicle_id, nonce, previous_mileage, mileage),
+        // where the nonce is the vehicle's mileage record count before this reading
+        pub signature: [u8; 64],
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct MileageRecord {
+        pub mileage: u32,
+        pub reported_by: AccountId,
+        pub timestamp: u64,
+        pub attestation: Option<MileageAttestation>,
+    }
+
+    impl Migrate for MileageRecord {
+        const VERSION: u16 = 1;
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ServiceType {
+        Inspection,
+        OilChange,
+        Tires,
+        Brakes,
+        Battery,
+        Repair,
+        Other,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ServiceRecord {
+        pub service_type: ServiceType,
+        pub odometer: u32,
+        pub parts_hash: [u8; 32],
+        pub cost: Balance,
+        pub timestamp: u64,
+        pub provider: AccountId,
+    }
+
+    impl Migrate for ServiceRecord {
+        const VERSION: u16 = 1;
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ServiceVisit {
+        pub provider: AccountId,
+        pub started_at: u64,
+        pub previous_status: VehicleStatus,
+    }
+
+    impl Migrate for ServiceVisit {
+        const VERSION: u16 = 1;
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ServiceInterval {
+        pub service_type: ServiceType,
+        pub mileage_interval: Option<u32>,
+        pub time_interval: Option<u64>,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ServiceDue {
+        pub service_type: ServiceType,
+        pub due_mileage: Option<u32>,
+        pub due_at: Option<u64>,
+        pub overdue: bool,
+    }
+
+    /// Clients match on the SCALE variant index, so new variants are only ever appended.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        VehicleNotFound,
+        VehicleAlreadyRegistered,
+        DuplicateVin,
+        DuplicateLicensePlate,
+        InvalidVehicleData,
+        SensorAlreadyRegistered,
+        OperatorNotAuthorized,
+        Unauthorized,
+        MileageRollback,
+        NoPendingTransfer,
+        InvalidTransferTarget,
+        VehicleDeregistered,
+        RetiredVin,
+        InvalidStatusTransition,
+        SensorNotFound,
+        SensorAlreadyInstalled,
+        InvalidConfiguration,
+        GeofenceNotFound,
+        InvalidGeofence,
+        GeofenceAlreadyAttached,
+        GeofenceLimitReached,
+        InvalidCoordinates,
+        StaleLocation,
+        ImplausibleLocationJump,
+        SensorKeyMissing,
+        InvalidAttestation,
+        ServiceInProgress,
+        NoServiceInProgress,
+        OperatorExpired,
+        InvalidOperatorExpiry,
+        NoOperatorAssigned,
+        UpgradeFailed,
+        StorageUpToDate,
+        Paused,
+        BatchTooLarge,
+        BatchAborted,
+        InvalidVinLength,
+        InvalidVinCharacter,
+        InvalidVinCheckDigit,
+        InvalidVinModelYear,
+        VinYearMismatch,
+        VinManufacturerMismatch,
+        DidNotFound,
+        DidNotActive,
+        DidEntityMismatch,
+        DidNotControlled,
+        DidAlreadyBound,
+        DidRegistryCallFailed,
+        DidRegistryNotConfigured,
+        StatusTransitionNotPermitted,
+        StatusReasonRequired,
+        InvalidStatusReason,
+        NotPaused,
+        SensorRetired,
+        SensorLimitReached,
+        OperatorNotExpired,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl From<AccessControlError> for Error {
+        fn from(error: AccessControlError) -> Self {
+            match error {
+                AccessControlError::MissingRole => Error::Unauthorized,
+            }
+        }
+    }
+
+    impl From<PausableError> for Error {
+        fn from(error: PausableError) -> Self {
+            match error {
+                PausableError::Paused => Error::Paused,
+            }
+        }
     }
 
+    impl From<OwnableError> for Error {
+        fn from(error: OwnableError) -> Self {
+            match error {
+                OwnableError::NotOwner | OwnableError::NotPendingOwner => Error::Unauthorized,
+            }
+        }
+    }
+
+    /// Struct-valued records live in `VersionedMapping`s so they can be migrated as they
+    /// are read. Vec-valued histories and the id indexes and counters stay plain
+    /// `Mapping`s; changing one of those layouts means moving it to a new field.
     #[ink(storage)]
     pub struct VehicleRegistry {
-        vehicles: Mapping<String, Vehicle>,
-        sensors: Mapping<String, Sensor>,
-        owner_vehicles: Mapping<AccountId, Vec<String>>,
-        operator_vehicles: Mapping<AccountId, Vec<String>>,
-        vehicle_sensors: Mapping<String, Vec<String>>,
+        vehicles: VersionedMapping<String, Vehicle>,
+        sensors: VersionedMapping<String, Sensor>,
+        vehicle_ids: Mapping<u32, String>,
+        vehicle_id_count: u32,
+        owner_vehicle_count: Mapping<AccountId, u32>,
+        owner_vehicles: Mapping<(AccountId, u32), String>,
+        owner_vehicle_index: Mapping<String, u32>,
+        operator_vehicle_count: Mapping<AccountId, u32>,
+        operator_vehicles: Mapping<(AccountId, u32), String>,
+        operator_vehicle_index: Mapping<String, u32>,
+        vehicle_sensor_count: Mapping<String, u32>,
+        vehicle_sensors: Mapping<(String, u32), String>,
+        vehicle_sensor_index: Mapping<String, u32>,
         vin_to_id: Mapping<String, String>,
         license_to_id: Mapping<String, String>,
-        stats: RegistryStats,
-        owner: AccountId,
+        stats: StatsTotals,
+        ownership: Ownable,
+        access: AccessControl,
+        pausable: Lazy<Pausable>,
+        /// Layout version of the data in storage. New root-level state has to live in
+        /// its own `Mapping`/`Lazy` cell so this struct keeps decoding across upgrades.
+        storage_version: u32,
+        max_batch_size: Lazy<u32>,
+        vin_check_digit_required: Mapping<VinRegion, bool>,
+        vin_model_year_required: Mapping<VinRegion, bool>,
+        wmi_manufacturers: Mapping<String, String>,
+        did_registry: Lazy<Option<AccountId>>,
+        did_vehicles: Mapping<String, String>,
+        did_sensors: Mapping<String, String>,
+        status_records: VersionedMapping<(String, u32), StatusRecord>,
+        status_record_count: Mapping<String, u32>,
+        vehicle_status_counts: Mapping<VehicleStatus, u32>,
+        engine_type_counts: Mapping<EngineType, u32>,
+        sensor_type_counts: Mapping<SensorType, u32>,
+        sensor_status_counts: Mapping<SensorStatus, u32>,
         authorized_operators: Mapping<AccountId, bool>,
+        operator_assignments: VersionedMapping<String, OperatorAssignment>,
+        pending_transfers: VersionedMapping<String, PendingTransfer>,
+        ownership_history: Mapping<String, Vec<OwnershipRecord>>,
+        retired_vins: Mapping<String, String>,
+        plate_history: Mapping<String, Vec<PlateRecord>>,
+        plate_assignments: Mapping<String, Vec<PlateAssignment>>,
+        sensor_calibrations: Mapping<String, Vec<CalibrationRecord>>,
+        sensor_installations: Mapping<String, Vec<InstallationRecord>>,
+        location_buffers: VersionedMapping<String, LocationBuffer>,
+        location_points: VersionedMapping<(String, u32), Location>,
+        location_history_capacity: u32,
+        geofences: VersionedMapping<u32, Geofence>,
+        next_geofence_id: u32,
+        vehicle_geofences: Mapping<String, Vec<u32>>,
+        geofence_presence: Mapping<(String, u32), bool>,
+        max_speed_kmh: u32,
+        sensor_keys: Mapping<String, [u8; 32]>,
+        mileage_records: VersionedMapping<(String, u32), MileageRecord>,
+        mileage_record_count: Mapping<String, u32>,
+        service_visits: VersionedMapping<String, ServiceVisit>,
+        service_records: VersionedMapping<(String, u32), ServiceRecord>,
+        service_record_count: Mapping<String, u32>,
+        service_intervals: Mapping<String, Vec<ServiceInterval>>,
+        last_services: VersionedMapping<(String, ServiceType), ServiceRecord>,
     }
 
     #[ink(event)]
@@ -189,6 +786,7 @@ mod vehicle_registry {
         old_status: VehicleStatus,
         new_status: VehicleStatus,
         changed_by: AccountId,
+        reason: StatusReason,
     }
 
     #[ink(event)]
@@ -201,6 +799,47 @@ mod vehicle_registry {
         registered_by: AccountId,
     }
 
+    #[ink(event)]
+    pub struct SensorStatusChanged {
+        #[ink(topic)]
+        sensor_id: String,
+        #[ink(topic)]
+        vehicle_id: String,
+        old_status: SensorStatus,
+        new_status: SensorStatus,
+        changed_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct SensorCalibrated {
+        #[ink(topic)]
+        sensor_id: String,
+        #[ink(topic)]
+        technician: AccountId,
+        certificate_hash: [u8; 32],
+        calibrated_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct SensorRemoved {
+        #[ink(topic)]
+        sensor_id: String,
+        #[ink(topic)]
+        vehicle_id: String,
+        removed_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct SensorReassigned {
+        #[ink(topic)]
+        sensor_id: String,
+        #[ink(topic)]
+        from_vehicle_id: String,
+        #[ink(topic)]
+        to_vehicle_id: String,
+        reassigned_by: AccountId,
+    }
+
     #[ink(event)]
     pub struct OperatorAssigned {
         #[ink(topic)]
@@ -209,15 +848,165 @@ mod vehicle_registry {
         operator: AccountId,
         #[ink(topic)]
         assigned_by: AccountId,
+        permissions: OperatorPermissions,
+        expires_at: Option<u64>,
     }
 
     #[ink(event)]
-    pub struct LocationUpdated {
+    pub struct OperatorUnassigned {
         #[ink(topic)]
         vehicle_id: String,
-        latitude: i32,
-        longitude: i32,
-        timestamp: u64,
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        unassigned_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OperatorExpired {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        operator: AccountId,
+        expired_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct LocationUpdated {
+        #[ink(topic)]
+        vehicle_id: String,
+        latitude: i32,
+        longitude: i32,
+        timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct MileageUpdated {
+        #[ink(topic)]
+        vehicle_id: String,
+        previous_mileage: u32,
+        mileage: u32,
+        reported_by: AccountId,
+        attested: bool,
+    }
+
+    #[ink(event)]
+    pub struct ServiceStarted {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        provider: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ServiceRecorded {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        provider: AccountId,
+        service_type: ServiceType,
+        odometer: u32,
+        cost: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ServiceCompleted {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        provider: AccountId,
+        restored_status: VehicleStatus,
+    }
+
+    #[ink(event)]
+    pub struct GeofenceCreated {
+        #[ink(topic)]
+        geofence_id: u32,
+        #[ink(topic)]
+        owner: AccountId,
+        kind: GeofenceKind,
+    }
+
+    #[ink(event)]
+    pub struct GeofenceEntered {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        geofence_id: u32,
+        latitude: i32,
+        longitude: i32,
+        timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct GeofenceExited {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        geofence_id: u32,
+        latitude: i32,
+        longitude: i32,
+        timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct GeofenceViolation {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        geofence_id: u32,
+        kind: GeofenceKind,
+        latitude: i32,
+        longitude: i32,
+        suspended: bool,
+    }
+
+    #[ink(event)]
+    pub struct LicensePlateChanged {
+        #[ink(topic)]
+        vehicle_id: String,
+        old_plate: String,
+        new_plate: String,
+        changed_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct VehicleDeregistered {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        owner: AccountId,
+        vin: String,
+        retired_sensors: u32,
+        deregistered_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferProposed {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferCancelled {
+        #[ink(topic)]
+        vehicle_id: String,
+        cancelled_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        vehicle_id: String,
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+        operator_kept: bool,
     }
 
     impl Default for VehicleRegistry {
@@ -230,83 +1019,130 @@ mod vehicle_registry {
         #[ink(constructor)]
         pub fn new() -> Self {
             Self {
-                vehicles: Mapping::default(),
-                sensors: Mapping::default(),
+                vehicles: VersionedMapping::default(),
+                sensors: VersionedMapping::default(),
+                vehicle_ids: Mapping::default(),
+                vehicle_id_count: 0,
+                owner_vehicle_count: Mapping::default(),
                 owner_vehicles: Mapping::default(),
+                owner_vehicle_index: Mapping::default(),
+                operator_vehicle_count: Mapping::default(),
                 operator_vehicles: Mapping::default(),
+                operator_vehicle_index: Mapping::default(),
+                vehicle_sensor_count: Mapping::default(),
                 vehicle_sensors: Mapping::default(),
+                vehicle_sensor_index: Mapping::default(),
                 vin_to_id: Mapping::default(),
                 license_to_id: Mapping::default(),
-                stats: RegistryStats::default(),
-                owner: Self::env().caller(),
+                stats: StatsTotals::default(),
+                ownership: Ownable::new(Self::env().caller()),
+                access: AccessControl::with_all_roles(Self::env().caller()),
+                pausable: Lazy::new(),
+                storage_version: STORAGE_VERSION,
+                max_batch_size: Lazy::new(),
+                vin_check_digit_required: Mapping::default(),
+                vin_model_year_required: Mapping::default(),
+                wmi_manufacturers: Mapping::default(),
+                did_registry: Lazy::new(),
+                did_vehicles: Mapping::default(),
+                did_sensors: Mapping::default(),
+                status_records: VersionedMapping::default(),
+                status_record_count: Mapping::default(),
+                vehicle_status_counts: Mapping::default(),
+                engine_type_counts: Mapping::default(),
+                sensor_type_counts: Mapping::default(),
+                sensor_status_counts: Mapping::default(),
                 authorized_operators: Mapping::default(),
+                operator_assignments: VersionedMapping::default(),
+                pending_transfers: VersionedMapping::default(),
+                ownership_history: Mapping::default(),
+                retired_vins: Mapping::default(),
+                plate_history: Mapping::default(),
+                plate_assignments: Mapping::default(),
+                sensor_calibrations: Mapping::default(),
+                sensor_installations: Mapping::default(),
+                location_buffers: VersionedMapping::default(),
+                location_points: VersionedMapping::default(),
+                location_history_capacity: DEFAULT_LOCATION_HISTORY_CAPACITY,
+                geofences: VersionedMapping::default(),
+                next_geofence_id: 0,
+                vehicle_geofences: Mapping::default(),
+                geofence_presence: Mapping::default(),
+                max_speed_kmh: DEFAULT_MAX_SPEED_KMH,
+                sensor_keys: Mapping::default(),
+                mileage_records: VersionedMapping::default(),
+                mileage_record_count: Mapping::default(),
+                service_visits: VersionedMapping::default(),
+                service_records: VersionedMapping::default(),
+                service_record_count: Mapping::default(),
+                service_intervals: Mapping::default(),
+                last_services: VersionedMapping::default(),
             }
         }
 
         #[ink(message)]
-        pub fn register_vehicle(&mut self, registration: VehicleRegistration) -> Result<(), String> {
-            if self.vehicles.contains(&registration.vehicle_id) {
-                return Err(String::from("Vehicle already registered"));
-            }
+        pub fn register_vehicle(&mut self, registration: VehicleRegistration) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Registration)?;
 
-            if self.vin_to_id.contains(&registration.vin) {
-                return Err(String::from("VIN already registered"));
-            }
+            self.check_vehicle_registration(&registration)?;
+            self.insert_vehicle(registration);
+            Ok(())
+        }
 
-            if self.license_to_id.contains(&registration.license_plate) {
-                return Err(String::from("License plate already registered"));
-            }
+        /// Registers several vehicles in one call. The returned list has one entry per
+        /// input, in order. In `BatchMode::Atomic` nothing is written unless every entry
+        /// is valid; otherwise the entries that would have succeeded report `BatchAborted`.
+        #[ink(message)]
+        pub fn register_vehicles(
+            &mut self,
+            registrations: Vec<VehicleRegistration>,
+            mode: BatchMode,
+        ) -> Result<Vec<Result<()>>> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Registration)?;
+            self.ensure_batch_size(registrations.len())?;
 
-            if registration.vehicle_id.is_empty() || registration.vin.is_empty() || registration.make.is_empty() {
-                return Err(String::from("Invalid vehicle data"));
+            if mode == BatchMode::BestEffort {
+                return Ok(registrations
+                    .into_iter()
+                    .map(|registration| {
+                        self.check_vehicle_registration(&registration)?;
+                        self.insert_vehicle(registration);
+                        Ok(())
+                    })
+                    .collect());
             }
 
-            let caller = self.env().caller();
-            let timestamp = self.env().block_timestamp();
-
-            let vehicle = Vehicle {
-                id: registration.vehicle_id.clone(),
-                did_identifier: registration.did_identifier,
-                owner: caller,
-                operator: None,
-                vin: registration.vin.clone(),
-                make: registration.make.clone(),
-                model: registration.model.clone(),
-                year: registration.year,
-                license_plate: registration.license_plate.clone(),
-                engine_type: registration.engine_type.clone(),
-                battery_capacity: registration.battery_capacity,
-                status: VehicleStatus::Active,
-                registered_at: timestamp,
-                last_updated: timestamp,
-                mileage: 0,
-                location: None,
-            };
-
-            self.vehicles.insert(&registration.vehicle_id, &vehicle);
-            self.vin_to_id.insert(&registration.vin, &registration.vehicle_id);
-            self.license_to_id.insert(&registration.license_plate, &registration.vehicle_id);
-
-            let mut owner_list = self.owner_vehicles.get(caller).unwrap_or_default();
-            owner_list.push(registration.vehicle_id.clone());
-            self.owner_vehicles.insert(caller, &owner_list);
+            let results: Vec<Result<()>> = registrations
+                .iter()
+                .enumerate()
+                .map(|(index, registration)| {
+                    self.check_vehicle_registration(registration)?;
+                    for earlier in &registrations[..index] {
+                        if earlier.vehicle_id == registration.vehicle_id {
+                            return Err(Error::VehicleAlreadyRegistered);
+                        }
+                        if earlier.vin == registration.vin {
+                            return Err(Error::DuplicateVin);
+                        }
+                        if earlier.license_plate == registration.license_plate {
+                            return Err(Error::DuplicateLicensePlate);
+                        }
+                        if earlier.did_identifier == registration.did_identifier {
+                            return Err(Error::DidAlreadyBound);
+                        }
+                    }
+                    Ok(())
+                })
+                .collect();
 
-            self.stats.total_vehicles = self.stats.total_vehicles.saturating_add(1);
-            self.stats.active_vehicles = self.stats.active_vehicles.saturating_add(1);
-            
-            if registration.engine_type == EngineType::Electric {
-                self.stats.electric_vehicles = self.stats.electric_vehicles.saturating_add(1);
+            if results.iter().any(|result| result.is_err()) {
+                return Ok(results.into_iter().map(|result| result.and(Err(Error::BatchAborted))).collect());
             }
 
-            self.env().emit_event(VehicleRegistered {
-                vehicle_id: registration.vehicle_id,
-                owner: caller,
-                vin: registration.vin,
-                make: registration.make,
-                model: registration.model,
-            });
-
-            Ok(())
+            for registration in registrations {
+                self.insert_vehicle(registration);
+            }
+            Ok(results)
         }
 
         #[ink(message)]
@@ -314,121 +1150,195 @@ mod vehicle_registry {
             &mut self,
             vehicle_id: String,
             operator: AccountId,
-        ) -> Result<(), String> {
-            let mut vehicle = self.vehicles.get(&vehicle_id)
-                .ok_or("Vehicle not found")?;
+            permissions: OperatorPermissions,
+            expires_at: Option<u64>,
+        ) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
 
             let caller = self.env().caller();
-            if vehicle.owner != caller && caller != self.owner {
-                return Err(String::from("Unauthorized"));
+            if vehicle.owner != caller && !self.access.has_role(Role::FleetManager, caller) {
+                return Err(Error::Unauthorized);
             }
 
             if !self.authorized_operators.get(operator).unwrap_or(false) {
-                return Err(String::from("Operator not authorized"));
+                return Err(Error::OperatorNotAuthorized);
             }
 
-            if let Some(old_operator) = vehicle.operator {
-                let mut old_operator_list = self.operator_vehicles.get(old_operator).unwrap_or_default();
-                old_operator_list.retain(|id| id != &vehicle_id);
-                self.operator_vehicles.insert(old_operator, &old_operator_list);
+            let timestamp = self.env().block_timestamp();
+            if expires_at.is_some_and(|expires_at| expires_at <= timestamp) {
+                return Err(Error::InvalidOperatorExpiry);
+            }
+
+            if !self.lapse_expired_operator(&mut vehicle) {
+                self.unassign(&mut vehicle, caller);
             }
 
             vehicle.operator = Some(operator);
-            vehicle.last_updated = self.env().block_timestamp();
+            vehicle.last_updated = timestamp;
             self.vehicles.insert(&vehicle_id, &vehicle);
 
-            let mut operator_list = self.operator_vehicles.get(operator).unwrap_or_default();
-            operator_list.push(vehicle_id.clone());
-            self.operator_vehicles.insert(operator, &operator_list);
+            self.operator_assignments.insert(
+                &vehicle_id,
+                &OperatorAssignment {
+                    operator,
+                    permissions,
+                    assigned_at: timestamp,
+                    expires_at,
+                },
+            );
+            self.add_operator_vehicle(operator, &vehicle_id);
 
             self.env().emit_event(OperatorAssigned {
                 vehicle_id,
                 operator,
                 assigned_by: caller,
+                permissions,
+                expires_at,
             });
 
             Ok(())
         }
 
         #[ink(message)]
-        pub fn register_sensor(&mut self, registration: SensorRegistration) -> Result<(), String> {
-            if !self.vehicles.contains(&registration.vehicle_id) {
-                return Err(String::from("Vehicle not found"));
+        pub fn unassign_operator(&mut self, vehicle_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            if vehicle.owner != caller && !self.access.has_role(Role::FleetManager, caller) {
+                return Err(Error::Unauthorized);
             }
 
-            if self.sensors.contains(&registration.sensor_id) {
-                return Err(String::from("Sensor already registered"));
+            if self.unassign(&mut vehicle, caller).is_none() {
+                return Err(Error::NoOperatorAssigned);
             }
 
-            let vehicle = self.vehicles.get(&registration.vehicle_id).unwrap();
+            vehicle.last_updated = self.env().block_timestamp();
+            self.vehicles.insert(&vehicle_id, &vehicle);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn resign_operator(&mut self, vehicle_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+
             let caller = self.env().caller();
-            
-            if vehicle.owner != caller && vehicle.operator != Some(caller) && caller != self.owner {
-                return Err(String::from("Unauthorized"));
+            if vehicle.operator != Some(caller) {
+                return Err(Error::Unauthorized);
             }
 
-            let timestamp = self.env().block_timestamp();
+            self.unassign(&mut vehicle, caller);
+            vehicle.last_updated = self.env().block_timestamp();
+            self.vehicles.insert(&vehicle_id, &vehicle);
+            Ok(())
+        }
 
-            let sensor = Sensor {
-                id: registration.sensor_id.clone(),
-                did_identifier: registration.did_identifier,
-                vehicle_id: registration.vehicle_id.clone(),
-                sensor_type: registration.sensor_type.clone(),
-                manufacturer: registration.manufacturer,
-                model: registration.model,
-                status: SensorStatus::Active,
-                installed_at: timestamp,
-                last_calibrated: timestamp,
-                accuracy: registration.accuracy,
-            };
+        /// Clears an operator assignment whose expiry has passed. Callable by anyone.
+        #[ink(message)]
+        pub fn expire_operator(&mut self, vehicle_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
 
-            self.sensors.insert(&registration.sensor_id, &sensor);
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
 
-            let mut vehicle_sensor_list = self.vehicle_sensors.get(&registration.vehicle_id).unwrap_or_default();
-            vehicle_sensor_list.push(registration.sensor_id.clone());
-            self.vehicle_sensors.insert(&registration.vehicle_id, &vehicle_sensor_list);
+            if vehicle.operator.is_none() {
+                return Err(Error::NoOperatorAssigned);
+            }
 
-            self.stats.total_sensors = self.stats.total_sensors.saturating_add(1);
-            self.stats.active_sensors = self.stats.active_sensors.saturating_add(1);
+            if !self.lapse_expired_operator(&mut vehicle) {
+                return Err(Error::OperatorNotExpired);
+            }
 
-            self.env().emit_event(SensorRegistered {
-                sensor_id: registration.sensor_id,
-                vehicle_id: registration.vehicle_id,
-                sensor_type: registration.sensor_type,
-                registered_by: caller,
-            });
+            vehicle.last_updated = self.env().block_timestamp();
+            self.vehicles.insert(&vehicle_id, &vehicle);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn register_sensor(&mut self, registration: SensorRegistration) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Registration)?;
 
+            let caller = self.env().caller();
+            self.check_sensor_registration(&registration, caller)?;
+            self.insert_sensor(registration, caller);
             Ok(())
         }
 
+        /// Sensor counterpart of `register_vehicles`, with the same result and mode semantics.
         #[ink(message)]
-        pub fn update_vehicle_status(
+        pub fn register_sensors(
             &mut self,
-            vehicle_id: String,
-            new_status: VehicleStatus,
-        ) -> Result<(), String> {
-            let mut vehicle = self.vehicles.get(&vehicle_id)
-                .ok_or("Vehicle not found")?;
+            registrations: Vec<SensorRegistration>,
+            mode: BatchMode,
+        ) -> Result<Vec<Result<()>>> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Registration)?;
+            self.ensure_batch_size(registrations.len())?;
 
             let caller = self.env().caller();
-            if vehicle.owner != caller && vehicle.operator != Some(caller) && caller != self.owner {
-                return Err(String::from("Unauthorized"));
+            if mode == BatchMode::BestEffort {
+                return Ok(registrations
+                    .into_iter()
+                    .map(|registration| {
+                        self.check_sensor_registration(&registration, caller)?;
+                        self.insert_sensor(registration, caller);
+                        Ok(())
+                    })
+                    .collect());
             }
 
-            let old_status = vehicle.status.clone();
-            
-            if old_status == VehicleStatus::Active && new_status != VehicleStatus::Active {
-                self.stats.active_vehicles = self.stats.active_vehicles.saturating_sub(1);
-            } else if old_status != VehicleStatus::Active && new_status == VehicleStatus::Active {
-                self.stats.active_vehicles = self.stats.active_vehicles.saturating_add(1);
+            let results: Vec<Result<()>> = registrations
+                .iter()
+                .enumerate()
+                .map(|(index, registration)| {
+                    self.check_sensor_registration(registration, caller)?;
+                    let mut queued: u32 = 0;
+                    for earlier in &registrations[..index] {
+                        if earlier.sensor_id == registration.sensor_id {
+                            return Err(Error::SensorAlreadyRegistered);
+                        }
+                        if earlier.did_identifier == registration.did_identifier {
+                            return Err(Error::DidAlreadyBound);
+                        }
+                        if earlier.vehicle_id == registration.vehicle_id {
+                            queued = queued.saturating_add(1);
+                        }
+                    }
+                    self.ensure_sensor_capacity(&registration.vehicle_id, queued)
+                })
+                .collect();
+
+            if results.iter().any(|result| result.is_err()) {
+                return Ok(results.into_iter().map(|result| result.and(Err(Error::BatchAborted))).collect());
             }
 
-            vehicle.status = new_status.clone();
-            vehicle.last_updated = self.env().block_timestamp();
-            self.vehicles.insert(&vehicle_id, &vehicle);
+            for registration in registrations {
+                self.insert_sensor(registration, caller);
+            }
+            Ok(results)
+        }
 
-            self.env().emit_event(VehicleStatusChanged {
-                vehicle_id,
+        #[ink(message)]
+        pub fn update_sensor_status(&mut self, sensor_id: String, new_status: SensorStatus) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let caller = self.env().caller();
+            let mut sensor = self.managed_sensor(&sensor_id, caller)?;
+
+            if new_status == SensorStatus::Retired {
+                return Err(Error::InvalidStatusTransition);
+            }
+
+            let old_status = sensor.status.clone();
+            self.set_sensor_status(&mut sensor, new_status.clone());
+            self.sensors.insert(&sensor_id, &sensor);
+
+            self.env().emit_event(SensorStatusChanged {
+                sensor_id,
+                vehicle_id: sensor.vehicle_id,
                 old_status,
                 new_status,
                 changed_by: caller,
@@ -438,322 +1348,4878 @@ mod vehicle_registry {
         }
 
         #[ink(message)]
-        pub fn update_location(
+        pub fn record_calibration(
             &mut self,
-            vehicle_id: String,
-            latitude: i32,
-            longitude: i32,
-        ) -> Result<(), String> {
-            let mut vehicle = self.vehicles.get(&vehicle_id)
-                .ok_or("Vehicle not found")?;
+            sensor_id: String,
+            certificate_hash: [u8; 32],
+            technician: AccountId,
+        ) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
 
             let caller = self.env().caller();
-            if vehicle.owner != caller && vehicle.operator != Some(caller) && caller != self.owner {
-                return Err(String::from("Unauthorized"));
+            let mut sensor = self.managed_sensor(&sensor_id, caller)?;
+            let timestamp = self.env().block_timestamp();
+
+            sensor.last_calibrated = timestamp;
+            if sensor.status == SensorStatus::Calibrating {
+                self.set_sensor_status(&mut sensor, SensorStatus::Active);
             }
+            self.sensors.insert(&sensor_id, &sensor);
 
-            let timestamp = self.env().block_timestamp();
-            
-            vehicle.location = Some(Location {
-                latitude,
-                longitude,
-                timestamp,
+            let mut calibrations = self.sensor_calibrations.get(&sensor_id).unwrap_or_default();
+            calibrations.push(CalibrationRecord {
+                certificate_hash,
+                technician,
+                calibrated_at: timestamp,
+                recorded_by: caller,
             });
-            vehicle.last_updated = timestamp;
-            self.vehicles.insert(&vehicle_id, &vehicle);
+            self.sensor_calibrations.insert(&sensor_id, &calibrations);
 
-            self.env().emit_event(LocationUpdated {
-                vehicle_id,
-                latitude,
-                longitude,
-                timestamp,
+            self.env().emit_event(SensorCalibrated {
+                sensor_id,
+                technician,
+                certificate_hash,
+                calibrated_at: timestamp,
             });
 
             Ok(())
         }
 
         #[ink(message)]
-        pub fn update_mileage(
-            &mut self,
-            vehicle_id: String,
-            mileage: u32,
-        ) -> Result<(), String> {
-            let mut vehicle = self.vehicles.get(&vehicle_id)
-                .ok_or("Vehicle not found")?;
+        pub fn remove_sensor(&mut self, sensor_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
 
             let caller = self.env().caller();
-            if vehicle.owner != caller && vehicle.operator != Some(caller) && caller != self.owner {
-                return Err(String::from("Unauthorized"));
-            }
+            let sensor = self.managed_sensor(&sensor_id, caller)?;
 
-            if mileage < vehicle.mileage {
-                return Err(String::from("Invalid mileage"));
-            }
+            let vehicle_id = sensor.vehicle_id.clone();
+            self.remove_vehicle_sensor(&vehicle_id, &sensor_id);
 
-            vehicle.mileage = mileage;
+            self.retire_sensor(sensor);
+
+            self.env().emit_event(SensorRemoved {
+                sensor_id,
+                vehicle_id,
+                removed_by: caller,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn reassign_sensor(&mut self, sensor_id: String, new_vehicle_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let caller = self.env().caller();
+            let mut sensor = self.managed_sensor(&sensor_id, caller)?;
+            let new_vehicle = self.registered_vehicle(&new_vehicle_id)?;
+
+            self.ensure_vehicle_access(&new_vehicle, caller, OperatorAction::ManageSensors)?;
+
+            if sensor.vehicle_id == new_vehicle_id {
+                return Err(Error::SensorAlreadyInstalled);
+            }
+            self.ensure_sensor_capacity(&new_vehicle_id, 0)?;
+
+            let timestamp = self.env().block_timestamp();
+            let old_vehicle_id = core::mem::replace(&mut sensor.vehicle_id, new_vehicle_id.clone());
+
+            self.remove_vehicle_sensor(&old_vehicle_id, &sensor_id);
+            self.add_vehicle_sensor(&new_vehicle_id, &sensor_id);
+
+            self.close_installation(&sensor_id, timestamp);
+            self.open_installation(&sensor_id, &new_vehicle_id, timestamp);
+
+            sensor.installed_at = timestamp;
+            self.sensors.insert(&sensor_id, &sensor);
+
+            self.env().emit_event(SensorReassigned {
+                sensor_id,
+                from_vehicle_id: old_vehicle_id,
+                to_vehicle_id: new_vehicle_id,
+                reassigned_by: caller,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_vehicle_status(
+            &mut self,
+            vehicle_id: String,
+            new_status: VehicleStatus,
+            reason: StatusReason,
+        ) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            self.ensure_vehicle_access(&vehicle, caller, OperatorAction::ChangeStatus)?;
+
+            // Deregistration has its own message, and an open service visit owns the status.
+            if new_status == VehicleStatus::Deregistered {
+                return Err(Error::InvalidStatusTransition);
+            }
+            if self.service_visits.contains(&vehicle_id) {
+                return Err(Error::ServiceInProgress);
+            }
+
+            let actor = if self.access.has_role(Role::FleetManager, caller) {
+                StatusActor::FleetManager
+            } else if vehicle.owner == caller {
+                StatusActor::Owner
+            } else {
+                StatusActor::Operator
+            };
+            check_status_reason(&new_status, reason)?;
+            check_status_transition(&vehicle.status, &new_status, actor)?;
+
+            let old_status = vehicle.status.clone();
+            self.set_vehicle_status(&mut vehicle, new_status.clone());
             vehicle.last_updated = self.env().block_timestamp();
             self.vehicles.insert(&vehicle_id, &vehicle);
 
-            Ok(())
+            self.record_status_change(&vehicle_id, old_status, new_status, reason, caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_location(
+            &mut self,
+            vehicle_id: String,
+            latitude: i32,
+            longitude: i32,
+        ) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            self.ensure_vehicle_access(&vehicle, caller, OperatorAction::UpdateLocation)?;
+
+            if !is_valid_coordinate(latitude, longitude) {
+                return Err(Error::InvalidCoordinates);
+            }
+
+            let timestamp = self.env().block_timestamp();
+
+            if let Some(ref previous) = vehicle.location {
+                if timestamp <= previous.timestamp {
+                    return Err(Error::StaleLocation);
+                }
+                if !within_speed_limit(previous, latitude, longitude, timestamp, self.max_speed_kmh) {
+                    return Err(Error::ImplausibleLocationJump);
+                }
+            }
+
+            let location = Location {
+                latitude,
+                longitude,
+                timestamp,
+            };
+
+            self.push_location(&vehicle_id, &location);
+
+            vehicle.location = Some(location.clone());
+            vehicle.last_updated = timestamp;
+            self.vehicles.insert(&vehicle_id, &vehicle);
+
+            self.env().emit_event(LocationUpdated {
+                vehicle_id,
+                latitude,
+                longitude,
+                timestamp,
+            });
+
+            self.evaluate_geofences(&mut vehicle, &location, caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn create_geofence(
+            &mut self,
+            shape: GeofenceShape,
+            kind: GeofenceKind,
+            suspend_on_violation: bool,
+        ) -> Result<u32> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            if !shape.is_valid() {
+                return Err(Error::InvalidGeofence);
+            }
+
+            let caller = self.env().caller();
+            let geofence_id = self.next_geofence_id;
+
+            let geofence = Geofence {
+                id: geofence_id,
+                owner: caller,
+                shape,
+                kind: kind.clone(),
+                suspend_on_violation,
+                created_at: self.env().block_timestamp(),
+            };
+
+            self.geofences.insert(geofence_id, &geofence);
+            self.next_geofence_id = self.next_geofence_id.saturating_add(1);
+
+            self.env().emit_event(GeofenceCreated {
+                geofence_id,
+                owner: caller,
+                kind,
+            });
+
+            Ok(geofence_id)
+        }
+
+        #[ink(message)]
+        pub fn attach_geofence(&mut self, vehicle_id: String, geofence_id: u32) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let vehicle = self.registered_vehicle(&vehicle_id)?;
+            let geofence = self.geofences.get(geofence_id).ok_or(Error::GeofenceNotFound)?;
+
+            let caller = self.env().caller();
+            if !self.access.has_role(Role::FleetManager, caller) && (vehicle.owner != caller || geofence.owner != caller) {
+                return Err(Error::Unauthorized);
+            }
+
+            let mut attached = self.vehicle_geofences.get(&vehicle_id).unwrap_or_default();
+            if attached.contains(&geofence_id) {
+                return Err(Error::GeofenceAlreadyAttached);
+            }
+            if attached.len() >= MAX_GEOFENCES_PER_VEHICLE {
+                return Err(Error::GeofenceLimitReached);
+            }
+
+            attached.push(geofence_id);
+            self.vehicle_geofences.insert(&vehicle_id, &attached);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn detach_geofence(&mut self, vehicle_id: String, geofence_id: u32) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            if vehicle.owner != caller && !self.access.has_role(Role::FleetManager, caller) {
+                return Err(Error::Unauthorized);
+            }
+
+            let mut attached = self.vehicle_geofences.get(&vehicle_id).unwrap_or_default();
+            if !attached.contains(&geofence_id) {
+                return Err(Error::GeofenceNotFound);
+            }
+
+            attached.retain(|id| *id != geofence_id);
+            self.vehicle_geofences.insert(&vehicle_id, &attached);
+            self.geofence_presence.remove((&vehicle_id, geofence_id));
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_mileage(
+            &mut self,
+            vehicle_id: String,
+            mileage: u32,
+            attestation: Option<MileageAttestation>,
+        ) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            self.ensure_vehicle_access(&vehicle, caller, OperatorAction::UpdateMileage)?;
+
+            if mileage < vehicle.mileage {
+                return Err(Error::MileageRollback);
+            }
+
+            let timestamp = self.env().block_timestamp();
+            let previous_mileage = vehicle.mileage;
+            let index = self.mileage_record_count.get(&vehicle_id).unwrap_or(0);
+
+            if let Some(ref attestation) = attestation {
+                self.verify_mileage_attestation(&vehicle_id, index, previous_mileage, mileage, attestation)?;
+            }
+
+            let attested = attestation.is_some();
+            self.mileage_records.insert(
+                (&vehicle_id, index),
+                &MileageRecord {
+                    mileage,
+                    reported_by: caller,
+                    timestamp,
+                    attestation,
+                },
+            );
+            self.mileage_record_count.insert(&vehicle_id, &index.saturating_add(1));
+
+            vehicle.mileage = mileage;
+            vehicle.last_updated = timestamp;
+            self.vehicles.insert(&vehicle_id, &vehicle);
+
+            self.env().emit_event(MileageUpdated {
+                vehicle_id,
+                previous_mileage,
+                mileage,
+                reported_by: caller,
+                attested,
+            });
+
+            Ok(())
+        }
+
+        /// Registers the public key of a sensor's DID so it can attest odometer readings.
+        /// Registrar only: the owner benefits from the readings, so cannot vouch for the key.
+        #[ink(message)]
+        pub fn set_sensor_key(&mut self, sensor_id: String, public_key: [u8; 32]) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+            self.access.ensure_role(Role::Registrar, self.env().caller())?;
+
+            let sensor = self.sensors.get(&sensor_id).ok_or(Error::SensorNotFound)?;
+            if sensor.status == SensorStatus::Retired {
+                return Err(Error::SensorRetired);
+            }
+            self.registered_vehicle(&sensor.vehicle_id)?;
+
+            self.sensor_keys.insert(&sensor_id, &public_key);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_license_plate(&mut self, vehicle_id: String, new_plate: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            if vehicle.owner != caller && !self.access.has_role(Role::Registrar, caller) {
+                return Err(Error::Unauthorized);
+            }
+
+            if new_plate.is_empty() {
+                return Err(Error::InvalidVehicleData);
+            }
+
+            if self.license_to_id.contains(&new_plate) {
+                return Err(Error::DuplicateLicensePlate);
+            }
+
+            let timestamp = self.env().block_timestamp();
+            let old_plate = core::mem::replace(&mut vehicle.license_plate, new_plate.clone());
+
+            self.license_to_id.remove(&old_plate);
+            self.license_to_id.insert(&new_plate, &vehicle_id);
+            self.close_plate_record(&vehicle_id, &old_plate, timestamp);
+            self.open_plate_record(&vehicle_id, &new_plate, timestamp);
+
+            vehicle.last_updated = timestamp;
+            self.vehicles.insert(&vehicle_id, &vehicle);
+
+            self.env().emit_event(LicensePlateChanged {
+                vehicle_id,
+                old_plate,
+                new_plate,
+                changed_by: caller,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn deregister_vehicle(&mut self, vehicle_id: String, reason: StatusReason) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            let actor = if self.access.has_role(Role::Registrar, caller) {
+                StatusActor::Registrar
+            } else if vehicle.owner == caller {
+                StatusActor::Owner
+            } else {
+                return Err(Error::Unauthorized);
+            };
+            check_status_reason(&VehicleStatus::Deregistered, reason)?;
+            check_status_transition(&vehicle.status, &VehicleStatus::Deregistered, actor)?;
+
+            self.retire_vehicle(vehicle, caller, reason);
+            Ok(())
+        }
+
+        /// Reads the vehicle's DID status from the configured `DidRegistry`. A suspended
+        /// DID suspends the vehicle and deactivates its sensors; a revoked DID deregisters
+        /// it. Returns whether anything changed. Callable by anyone, e.g. keepers.
+        #[ink(message)]
+        pub fn sync_did_status(&mut self, vehicle_id: String) -> Result<bool> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            self.sync_vehicle_did(&vehicle_id)
+        }
+
+        #[ink(message)]
+        pub fn sync_did_statuses(&mut self, vehicle_ids: Vec<String>) -> Result<Vec<Result<bool>>> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+            self.ensure_batch_size(vehicle_ids.len())?;
+
+            Ok(vehicle_ids
+                .iter()
+                .map(|vehicle_id| self.sync_vehicle_did(vehicle_id))
+                .collect())
+        }
+
+        #[ink(message)]
+        pub fn propose_ownership_transfer(
+            &mut self,
+            vehicle_id: String,
+            new_owner: AccountId,
+            keep_operator: bool,
+        ) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            if vehicle.owner != caller {
+                return Err(Error::Unauthorized);
+            }
+
+            if new_owner == vehicle.owner {
+                return Err(Error::InvalidTransferTarget);
+            }
+
+            let transfer = PendingTransfer {
+                new_owner,
+                keep_operator,
+                proposed_at: self.env().block_timestamp(),
+            };
+            self.pending_transfers.insert(&vehicle_id, &transfer);
+
+            self.env().emit_event(OwnershipTransferProposed {
+                vehicle_id,
+                owner: caller,
+                new_owner,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn accept_ownership_transfer(&mut self, vehicle_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+            let transfer = self.pending_transfers.get(&vehicle_id)
+                .ok_or(Error::NoPendingTransfer)?;
+
+            let caller = self.env().caller();
+            if transfer.new_owner != caller {
+                return Err(Error::Unauthorized);
+            }
+
+            let previous_owner = vehicle.owner;
+            let timestamp = self.env().block_timestamp();
+
+            let mut history = self.ownership_history.get(&vehicle_id).unwrap_or_default();
+            let acquired_at = history
+                .last()
+                .map_or(vehicle.registered_at, |record| record.released_at.max(vehicle.registered_at));
+            history.push(OwnershipRecord {
+                owner: previous_owner,
+                acquired_at,
+                released_at: timestamp,
+            });
+            self.ownership_history.insert(&vehicle_id, &history);
+
+            self.remove_owner_vehicle(previous_owner, &vehicle_id);
+            self.add_owner_vehicle(caller, &vehicle_id);
+
+            if !transfer.keep_operator {
+                self.clear_operator(&mut vehicle);
+            }
+
+            vehicle.owner = caller;
+            vehicle.last_updated = timestamp;
+            self.vehicles.insert(&vehicle_id, &vehicle);
+            self.pending_transfers.remove(&vehicle_id);
+
+            self.env().emit_event(OwnershipTransferred {
+                vehicle_id,
+                previous_owner,
+                new_owner: caller,
+                operator_kept: transfer.keep_operator,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_transfer(&mut self, vehicle_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let vehicle = self.vehicles.get(&vehicle_id)
+                .ok_or(Error::VehicleNotFound)?;
+            let transfer = self.pending_transfers.get(&vehicle_id)
+                .ok_or(Error::NoPendingTransfer)?;
+
+            let caller = self.env().caller();
+            if vehicle.owner != caller && transfer.new_owner != caller {
+                return Err(Error::Unauthorized);
+            }
+
+            self.pending_transfers.remove(&vehicle_id);
+
+            self.env().emit_event(OwnershipTransferCancelled {
+                vehicle_id,
+                cancelled_by: caller,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn begin_service(&mut self, vehicle_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            self.access.ensure_role(Role::ServiceProvider, caller)?;
+
+            if self.service_visits.contains(&vehicle_id) {
+                return Err(Error::ServiceInProgress);
+            }
+
+            let timestamp = self.env().block_timestamp();
+            let previous_status = vehicle.status.clone();
+            self.service_visits.insert(
+                &vehicle_id,
+                &ServiceVisit {
+                    provider: caller,
+                    started_at: timestamp,
+                    previous_status: previous_status.clone(),
+                },
+            );
+
+            self.set_vehicle_status(&mut vehicle, VehicleStatus::Maintenance);
+            vehicle.last_updated = timestamp;
+            self.vehicles.insert(&vehicle_id, &vehicle);
+
+            self.env().emit_event(ServiceStarted {
+                vehicle_id: vehicle_id.clone(),
+                provider: caller,
+            });
+            self.record_status_change(
+                &vehicle_id,
+                previous_status,
+                VehicleStatus::Maintenance,
+                StatusReason::ServiceStarted,
+                caller,
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn record_service(
+            &mut self,
+            vehicle_id: String,
+            service_type: ServiceType,
+            odometer: u32,
+            parts_hash: [u8; 32],
+            cost: Balance,
+        ) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            let visit = self.service_visits.get(&vehicle_id).ok_or(Error::NoServiceInProgress)?;
+            if visit.provider != caller || !self.access.has_role(Role::ServiceProvider, caller) {
+                return Err(Error::Unauthorized);
+            }
+
+            if odometer < vehicle.mileage {
+                return Err(Error::MileageRollback);
+            }
+
+            let record = ServiceRecord {
+                service_type: service_type.clone(),
+                odometer,
+                parts_hash,
+                cost,
+                timestamp: self.env().block_timestamp(),
+                provider: caller,
+            };
+
+            let index = self.service_record_count.get(&vehicle_id).unwrap_or(0);
+            self.service_records.insert((&vehicle_id, index), &record);
+            self.service_record_count.insert(&vehicle_id, &index.saturating_add(1));
+            self.last_services.insert((&vehicle_id, &service_type), &record);
+
+            self.env().emit_event(ServiceRecorded {
+                vehicle_id,
+                provider: caller,
+                service_type,
+                odometer,
+                cost,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn complete_service(&mut self, vehicle_id: String) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let mut vehicle = self.registered_vehicle(&vehicle_id)?;
+            let visit = self.service_visits.get(&vehicle_id).ok_or(Error::NoServiceInProgress)?;
+
+            let caller = self.env().caller();
+            if visit.provider != caller && vehicle.owner != caller && !self.access.has_role(Role::FleetManager, caller) {
+                return Err(Error::Unauthorized);
+            }
+
+            self.service_visits.remove(&vehicle_id);
+
+            // A suspension imposed during the visit (DID or geofence) outlives it.
+            if vehicle.status != VehicleStatus::Maintenance {
+                self.env().emit_event(ServiceCompleted {
+                    vehicle_id,
+                    provider: visit.provider,
+                    restored_status: vehicle.status,
+                });
+                return Ok(());
+            }
+
+            let old_status = vehicle.status.clone();
+            let restored_status = visit.previous_status;
+            self.set_vehicle_status(&mut vehicle, restored_status.clone());
+            vehicle.last_updated = self.env().block_timestamp();
+            self.vehicles.insert(&vehicle_id, &vehicle);
+
+            self.env().emit_event(ServiceCompleted {
+                vehicle_id: vehicle_id.clone(),
+                provider: visit.provider,
+                restored_status: restored_status.clone(),
+            });
+            self.record_status_change(&vehicle_id, old_status, restored_status, StatusReason::ServiceCompleted, caller);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_service_interval(
+            &mut self,
+            vehicle_id: String,
+            service_type: ServiceType,
+            mileage_interval: Option<u32>,
+            time_interval: Option<u64>,
+        ) -> Result<()> {
+            self.pausable.get_or_default().ensure_not_paused(PauseScope::Mutation)?;
+
+            let vehicle = self.registered_vehicle(&vehicle_id)?;
+
+            let caller = self.env().caller();
+            if vehicle.owner != caller && !self.access.has_role(Role::FleetManager, caller) {
+                return Err(Error::Unauthorized);
+            }
+
+            let mut intervals = self.service_intervals.get(&vehicle_id).unwrap_or_default();
+            intervals.retain(|interval| interval.service_type != service_type);
+            if mileage_interval.is_some() || time_interval.is_some() {
+                intervals.push(ServiceInterval {
+                    service_type,
+                    mileage_interval,
+                    time_interval,
+                });
+            }
+            self.service_intervals.insert(&vehicle_id, &intervals);
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn authorize_operator(&mut self, operator: AccountId) -> Result<()> {
+            self.access.ensure_role(Role::FleetManager, self.env().caller())?;
+
+            self.authorized_operators.insert(operator, &true);
+            Ok(())
+        }
+
+        /// With `cascade`, also unassigns the first `MAX_PAGE_SIZE` of the operator's vehicles.
+        /// Returns how many stay assigned; finish those with `release_operator_vehicles`.
+        #[ink(message)]
+        pub fn deauthorize_operator(&mut self, operator: AccountId, cascade: bool) -> Result<u32> {
+            let caller = self.env().caller();
+            self.access.ensure_role(Role::FleetManager, caller)?;
+
+            self.authorized_operators.insert(operator, &false);
+            if cascade {
+                self.release_vehicles_of(operator, MAX_PAGE_SIZE, caller);
+            }
+            Ok(self.operator_vehicle_count.get(operator).unwrap_or(0))
+        }
+
+        /// Unassigns up to `limit` of the operator's vehicles and returns how many remain.
+        /// Each unassignment shrinks the operator's index, so repeated calls need no cursor.
+        #[ink(message)]
+        pub fn release_operator_vehicles(&mut self, operator: AccountId, limit: u32) -> Result<u32> {
+            let caller = self.env().caller();
+            self.access.ensure_role(Role::FleetManager, caller)?;
+
+            self.release_vehicles_of(operator, limit.min(MAX_PAGE_SIZE), caller);
+            Ok(self.operator_vehicle_count.get(operator).unwrap_or(0))
+        }
+
+        #[ink(message)]
+        pub fn set_location_history_capacity(&mut self, capacity: u32) -> Result<()> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+
+            if capacity > MAX_LOCATION_HISTORY_CAPACITY {
+                return Err(Error::InvalidConfiguration);
+            }
+
+            self.location_history_capacity = capacity;
+            Ok(())
+        }
+
+        /// Caps the number of entries accepted by a single batch call.
+        #[ink(message)]
+        pub fn set_max_batch_size(&mut self, max_batch_size: u32) -> Result<()> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+
+            if max_batch_size == 0 || max_batch_size > MAX_BATCH_SIZE_LIMIT {
+                return Err(Error::InvalidConfiguration);
+            }
+
+            self.max_batch_size.set(&max_batch_size);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_max_batch_size(&self) -> u32 {
+            self.max_batch_size.get().unwrap_or(DEFAULT_MAX_BATCH_SIZE)
+        }
+
+        /// North-American VINs carry a check digit by default; other regions opt in.
+        #[ink(message)]
+        pub fn set_vin_check_digit_required(&mut self, region: VinRegion, required: bool) -> Result<()> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+
+            self.vin_check_digit_required.insert(region, &required);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_vin_check_digit_required(&self, region: VinRegion) -> bool {
+            self.vin_check_digit_required
+                .get(region)
+                .unwrap_or(region == VinRegion::NorthAmerica)
+        }
+
+        /// Position 10 encodes the model year in North America; elsewhere it is often
+        /// manufacturer-specific, so other regions opt in.
+        #[ink(message)]
+        pub fn set_vin_model_year_required(&mut self, region: VinRegion, required: bool) -> Result<()> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+
+            self.vin_model_year_required.insert(region, &required);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_vin_model_year_required(&self, region: VinRegion) -> bool {
+            self.vin_model_year_required
+                .get(region)
+                .unwrap_or(region == VinRegion::NorthAmerica)
+        }
+
+        /// Maps a World Manufacturer Identifier (the first three VIN characters) to the make
+        /// registrations must declare. `None` removes the mapping.
+        #[ink(message)]
+        pub fn set_wmi_manufacturer(&mut self, wmi: String, make: Option<String>) -> Result<()> {
+            self.access.ensure_role(Role::Registrar, self.env().caller())?;
+
+            if wmi.len() != 3 || wmi.bytes().any(|character| vin_value(character).is_none()) {
+                return Err(Error::InvalidVinCharacter);
+            }
+
+            match make {
+                Some(make) if !make.is_empty() => {
+                    self.wmi_manufacturers.insert(&wmi, &make);
+                }
+                Some(_) => return Err(Error::InvalidVehicleData),
+                None => self.wmi_manufacturers.remove(&wmi),
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_wmi_manufacturer(&self, wmi: String) -> Option<String> {
+            self.wmi_manufacturers.get(&wmi)
+        }
+
+        /// Once set, registrations must reference an active DID of the matching entity
+        /// type that the caller controls in this `DidRegistry` contract.
+        #[ink(message)]
+        pub fn set_did_registry(&mut self, did_registry: Option<AccountId>) -> Result<()> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+
+            self.did_registry.set(&did_registry);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_did_registry(&self) -> Option<AccountId> {
+            self.did_registry.get().flatten()
+        }
+
+        /// Sets the fastest plausible speed between consecutive fixes; `0` disables the check.
+        #[ink(message)]
+        pub fn set_max_speed(&mut self, max_speed_kmh: u32) -> Result<()> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+
+            self.max_speed_kmh = max_speed_kmh;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle(&self, vehicle_id: String) -> Option<Vehicle> {
+            self.vehicles.get(&vehicle_id)
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_by_did(&self, did_identifier: String) -> Option<Vehicle> {
+            let vehicle_id = self.did_vehicles.get(&did_identifier)?;
+            self.vehicles.get(&vehicle_id)
+        }
+
+        #[ink(message)]
+        pub fn get_sensor_by_did(&self, did_identifier: String) -> Option<Sensor> {
+            let sensor_id = self.did_sensors.get(&did_identifier)?;
+            self.sensors.get(&sensor_id)
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_by_vin(&self, vin: String) -> Option<Vehicle> {
+            if let Some(vehicle_id) = self.vin_to_id.get(&vin) {
+                self.vehicles.get(&vehicle_id)
+            } else {
+                None
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_by_license(&self, license_plate: String) -> Option<Vehicle> {
+            if let Some(vehicle_id) = self.license_to_id.get(&license_plate) {
+                self.vehicles.get(&vehicle_id)
+            } else {
+                None
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_sensor(&self, sensor_id: String) -> Option<Sensor> {
+            self.sensors.get(&sensor_id)
+        }
+
+        #[ink(message)]
+        pub fn get_calibration_history(&self, sensor_id: String) -> Result<Vec<CalibrationRecord>> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.sensor_calibrations.get(&sensor_id).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_installation_history(&self, sensor_id: String) -> Result<Vec<InstallationRecord>> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.sensor_installations.get(&sensor_id).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_sensors(&self, vehicle_id: String, offset: u32, limit: u32) -> Result<Vec<Sensor>> {
+            self.ensure_queries_allowed()?;
+
+            Ok(index_page(&self.vehicle_sensor_count, &self.vehicle_sensors, &vehicle_id, offset, limit)
+                .iter()
+                .filter_map(|sensor_id| self.sensors.get(sensor_id))
+                .collect())
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_sensor_count(&self, vehicle_id: String) -> u32 {
+            self.vehicle_sensor_count.get(&vehicle_id).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_owner_vehicles(&self, owner: AccountId, offset: u32, limit: u32) -> Result<Vec<Vehicle>> {
+            self.ensure_queries_allowed()?;
+
+            Ok(index_page(&self.owner_vehicle_count, &self.owner_vehicles, &owner, offset, limit)
+                .iter()
+                .filter_map(|vehicle_id| self.vehicles.get(vehicle_id))
+                .collect())
+        }
+
+        #[ink(message)]
+        pub fn get_owner_vehicle_count(&self, owner: AccountId) -> u32 {
+            self.owner_vehicle_count.get(owner).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_operator_vehicles(&self, operator: AccountId, offset: u32, limit: u32) -> Result<Vec<Vehicle>> {
+            self.ensure_queries_allowed()?;
+
+            Ok(index_page(&self.operator_vehicle_count, &self.operator_vehicles, &operator, offset, limit)
+                .iter()
+                .filter_map(|vehicle_id| self.vehicles.get(vehicle_id))
+                .collect())
+        }
+
+        /// Walks the registry from `cursor`, examining at most `MAX_VEHICLE_SCAN` entries per
+        /// call and returning up to `limit` vehicles matching `filter`.
+        #[ink(message)]
+        pub fn list_vehicles(&self, cursor: u32, limit: u32, filter: VehicleFilter) -> Result<VehiclePage> {
+            self.ensure_queries_allowed()?;
+
+            let limit = limit.min(MAX_PAGE_SIZE);
+            let scan_end = cursor.saturating_add(MAX_VEHICLE_SCAN).min(self.vehicle_id_count);
+            let mut vehicles = Vec::new();
+            let mut position = cursor;
+
+            while position < scan_end && (vehicles.len() as u32) < limit {
+                if let Some(vehicle) = self.vehicle_ids.get(position).and_then(|id| self.vehicles.get(&id)) {
+                    if filter.matches(&vehicle) {
+                        vehicles.push(vehicle);
+                    }
+                }
+                position = position.saturating_add(1);
+            }
+
+            let next_cursor = if position < self.vehicle_id_count { Some(position) } else { None };
+            Ok(VehiclePage { vehicles, next_cursor })
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_id_count(&self) -> u32 {
+            self.vehicle_id_count
+        }
+
+        #[ink(message)]
+        pub fn get_operator_vehicle_count(&self, operator: AccountId) -> u32 {
+            self.operator_vehicle_count.get(operator).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_plate_history(&self, vehicle_id: String) -> Result<Vec<PlateRecord>> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.plate_history.get(&vehicle_id).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_plate_holder_at(&self, license_plate: String, timestamp: u64) -> Option<String> {
+            self.plate_assignments
+                .get(&license_plate)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|assignment| {
+                    assignment.assigned_at <= timestamp
+                        && assignment.released_at.is_none_or(|released_at| timestamp < released_at)
+                })
+                .map(|assignment| assignment.vehicle_id)
+        }
+
+        #[ink(message)]
+        pub fn get_pending_transfer(&self, vehicle_id: String) -> Option<PendingTransfer> {
+            self.pending_transfers.get(&vehicle_id)
+        }
+
+        #[ink(message)]
+        pub fn get_ownership_history(&self, vehicle_id: String) -> Result<Vec<OwnershipRecord>> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.ownership_history.get(&vehicle_id).unwrap_or_default())
+        }
+
+        /// Returns up to `limit` of the most recent fixes with `from_ts <= timestamp <= to_ts`,
+        /// oldest first.
+        #[ink(message)]
+        pub fn get_location_history(
+            &self,
+            vehicle_id: String,
+            from_ts: u64,
+            to_ts: u64,
+            limit: u32,
+        ) -> Result<Vec<Location>> {
+            self.ensure_queries_allowed()?;
+
+            let Some(buffer) = self.location_buffers.get(&vehicle_id) else {
+                return Ok(Vec::new());
+            };
+
+            let mut points = Vec::new();
+            for offset in 1..=buffer.len {
+                if points.len() >= limit as usize {
+                    break;
+                }
+                let slot = (buffer.head + buffer.capacity - offset) % buffer.capacity;
+                if let Some(location) = self.location_points.get((&vehicle_id, slot)) {
+                    if location.timestamp < from_ts {
+                        break;
+                    }
+                    if location.timestamp <= to_ts {
+                        points.push(location);
+                    }
+                }
+            }
+            points.reverse();
+            Ok(points)
+        }
+
+        #[ink(message)]
+        pub fn get_location_history_capacity(&self) -> u32 {
+            self.location_history_capacity
+        }
+
+        #[ink(message)]
+        pub fn get_mileage_history(&self, vehicle_id: String, offset: u32, limit: u32) -> Result<Vec<MileageRecord>> {
+            self.ensure_queries_allowed()?;
+
+            let count = self.mileage_record_count.get(&vehicle_id).unwrap_or(0);
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+            Ok((offset..end)
+                .filter_map(|index| self.mileage_records.get((&vehicle_id, index)))
+                .collect())
+        }
+
+        #[ink(message)]
+        pub fn get_mileage_record_count(&self, vehicle_id: String) -> u32 {
+            self.mileage_record_count.get(&vehicle_id).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_status_history(&self, vehicle_id: String, offset: u32, limit: u32) -> Result<Vec<StatusRecord>> {
+            self.ensure_queries_allowed()?;
+
+            let count = self.status_record_count.get(&vehicle_id).unwrap_or(0);
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+            Ok((offset..end)
+                .filter_map(|index| self.status_records.get((&vehicle_id, index)))
+                .collect())
+        }
+
+        #[ink(message)]
+        pub fn get_status_record_count(&self, vehicle_id: String) -> u32 {
+            self.status_record_count.get(&vehicle_id).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_sensor_key(&self, sensor_id: String) -> Option<[u8; 32]> {
+            self.sensor_keys.get(&sensor_id)
+        }
+
+        #[ink(message)]
+        pub fn get_service_history(&self, vehicle_id: String, offset: u32, limit: u32) -> Result<Vec<ServiceRecord>> {
+            self.ensure_queries_allowed()?;
+
+            let count = self.service_record_count.get(&vehicle_id).unwrap_or(0);
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+            Ok((offset..end)
+                .filter_map(|index| self.service_records.get((&vehicle_id, index)))
+                .collect())
+        }
+
+        #[ink(message)]
+        pub fn get_service_record_count(&self, vehicle_id: String) -> u32 {
+            self.service_record_count.get(&vehicle_id).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_service_visit(&self, vehicle_id: String) -> Option<ServiceVisit> {
+            self.service_visits.get(&vehicle_id)
+        }
+
+        #[ink(message)]
+        pub fn get_service_intervals(&self, vehicle_id: String) -> Result<Vec<ServiceInterval>> {
+            self.ensure_queries_allowed()?;
+
+            Ok(self.service_intervals.get(&vehicle_id).unwrap_or_default())
+        }
+
+        /// Next due point for every configured interval, measured from the last service of
+        /// that type (or from registration if it was never serviced).
+        #[ink(message)]
+        pub fn get_service_due(&self, vehicle_id: String) -> Result<Vec<ServiceDue>> {
+            self.ensure_queries_allowed()?;
+
+            let Some(vehicle) = self.vehicles.get(&vehicle_id) else {
+                return Ok(Vec::new());
+            };
+            let now = self.env().block_timestamp();
+
+            Ok(self
+                .service_intervals
+                .get(&vehicle_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|interval| {
+                    let (last_odometer, last_at) = self
+                        .last_services
+                        .get((&vehicle_id, &interval.service_type))
+                        .map_or((0, vehicle.registered_at), |record| (record.odometer, record.timestamp));
+
+                    let due_mileage = interval
+                        .mileage_interval
+                        .map(|every| last_odometer.saturating_add(every));
+                    let due_at = interval.time_interval.map(|every| last_at.saturating_add(every));
+                    let overdue = due_mileage.is_some_and(|due| vehicle.mileage >= due)
+                        || due_at.is_some_and(|due| now >= due);
+
+                    ServiceDue {
+                        service_type: interval.service_type,
+                        due_mileage,
+                        due_at,
+                        overdue,
+                    }
+                })
+                .collect())
+        }
+
+        #[ink(message)]
+        pub fn get_max_speed(&self) -> u32 {
+            self.max_speed_kmh
+        }
+
+        #[ink(message)]
+        pub fn get_geofence(&self, geofence_id: u32) -> Option<Geofence> {
+            self.geofences.get(geofence_id)
+        }
+
+        #[ink(message)]
+        pub fn get_vehicle_geofences(&self, vehicle_id: String) -> Vec<u32> {
+            self.vehicle_geofences.get(&vehicle_id).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn get_stats(&self) -> RegistryStats {
+            RegistryStats {
+                total_vehicles: self.stats.total_vehicles,
+                active_vehicles: self.stats.active_vehicles,
+                total_sensors: self.stats.total_sensors,
+                active_sensors: self.stats.active_sensors,
+                electric_vehicles: self.stats.electric_vehicles,
+                vehicles_by_status: counter_breakdown(&self.vehicle_status_counts, VehicleStatus::ALL),
+                vehicles_by_engine_type: counter_breakdown(&self.engine_type_counts, EngineType::ALL),
+                sensors_by_type: counter_breakdown(&self.sensor_type_counts, SensorType::ALL),
+                sensors_by_status: counter_breakdown(&self.sensor_status_counts, SensorStatus::LIVE),
+            }
+        }
+
+        /// Rebuilds the stats from the vehicle records, `limit` vehicles at a time. Starting
+        /// at cursor 0 clears every counter; keep calling with the returned cursor until it
+        /// is `None`. Registration and mutation must stay paused for the whole run.
+        #[ink(message)]
+        pub fn recompute_stats(&mut self, cursor: u32, limit: u32) -> Result<Option<u32>> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+
+            let pausable = self.pausable.get_or_default();
+            if !pausable.is_paused(PauseScope::Registration) || !pausable.is_paused(PauseScope::Mutation) {
+                return Err(Error::NotPaused);
+            }
+
+            if cursor == 0 {
+                self.stats = StatsTotals::default();
+                for status in VehicleStatus::ALL {
+                    self.vehicle_status_counts.remove(status);
+                }
+                for engine_type in EngineType::ALL {
+                    self.engine_type_counts.remove(engine_type);
+                }
+                for sensor_type in SensorType::ALL {
+                    self.sensor_type_counts.remove(sensor_type);
+                }
+                for status in SensorStatus::LIVE {
+                    self.sensor_status_counts.remove(status);
+                }
+            }
+
+            let end = cursor.saturating_add(limit.min(MAX_PAGE_SIZE)).min(self.vehicle_id_count);
+            for position in cursor..end {
+                let Some(vehicle) = self.vehicle_ids.get(position).and_then(|id| self.vehicles.get(&id)) else {
+                    continue;
+                };
+
+                counter_add(&mut self.vehicle_status_counts, &vehicle.status);
+                if vehicle.status == VehicleStatus::Deregistered {
+                    continue;
+                }
+
+                self.stats.total_vehicles = self.stats.total_vehicles.saturating_add(1);
+                if vehicle.status == VehicleStatus::Active {
+                    self.stats.active_vehicles = self.stats.active_vehicles.saturating_add(1);
+                }
+                if vehicle.engine_type == EngineType::Electric {
+                    self.stats.electric_vehicles = self.stats.electric_vehicles.saturating_add(1);
+                }
+                counter_add(&mut self.engine_type_counts, &vehicle.engine_type);
+
+                let sensor_ids = self.vehicle_sensor_ids(&vehicle.id);
+                for sensor in sensor_ids.iter().filter_map(|sensor_id| self.sensors.get(sensor_id)) {
+                    self.stats.total_sensors = self.stats.total_sensors.saturating_add(1);
+                    if sensor.status == SensorStatus::Active {
+                        self.stats.active_sensors = self.stats.active_sensors.saturating_add(1);
+                    }
+                    counter_add(&mut self.sensor_type_counts, &sensor.sensor_type);
+                    counter_add(&mut self.sensor_status_counts, &sensor.status);
+                }
+            }
+
+            Ok((end < self.vehicle_id_count).then_some(end))
+        }
+
+        #[ink(message)]
+        pub fn get_operator_assignment(&self, vehicle_id: String) -> Option<OperatorAssignment> {
+            self.operator_assignments.get(&vehicle_id)
+        }
+
+        #[ink(message)]
+        pub fn is_operator_authorized(&self, operator: AccountId) -> bool {
+            self.authorized_operators.get(operator).unwrap_or(false)
+        }
+
+        #[ink(message)]
+        pub fn is_vehicle_active(&self, vehicle_id: String) -> bool {
+            if let Some(vehicle) = self.vehicles.get(&vehicle_id) {
+                vehicle.status == VehicleStatus::Active
+            } else {
+                false
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_owner(&self) -> Option<AccountId> {
+            self.ownership.owner()
+        }
+
+        #[ink(message)]
+        pub fn get_pending_owner(&self) -> Option<AccountId> {
+            self.ownership.pending_owner()
+        }
+
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<()> {
+            self.ownership.transfer_ownership(self.env().caller(), new_owner)?;
+            Ok(())
+        }
+
+        /// Completes a pending ownership transfer; the roles held by the previous owner move with it.
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if let Some(previous_owner) = self.ownership.accept_ownership(caller)? {
+                self.access.transfer_all_roles(previous_owner, Some(caller));
+            }
+            Ok(())
+        }
+
+        /// Leaves the contract without an owner and drops every role the owner held.
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            self.ownership.renounce_ownership(caller)?;
+            self.access.transfer_all_roles(caller, None);
+            Ok(())
+        }
+
+        /// Guardians may pause; lifting a pause is reserved for admins.
+        #[ink(message)]
+        pub fn pause(&mut self, scope: PauseScope) -> Result<()> {
+            let caller = self.env().caller();
+            if !self.access.has_role(Role::Guardian, caller) {
+                self.access.ensure_role(Role::Admin, caller)?;
+            }
+            let mut pausable = self.pausable.get_or_default();
+            pausable.pause(scope, caller);
+            self.pausable.set(&pausable);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unpause(&mut self, scope: PauseScope) -> Result<()> {
+            let caller = self.env().caller();
+            self.access.ensure_role(Role::Admin, caller)?;
+            let mut pausable = self.pausable.get_or_default();
+            pausable.unpause(scope, caller);
+            self.pausable.set(&pausable);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self, scope: PauseScope) -> bool {
+            self.pausable.get_or_default().is_paused(scope)
+        }
+
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<()> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+            self.env().set_code_hash(&code_hash).map_err(|_| Error::UpgradeFailed)?;
+
+            self.env().emit_event(Upgraded {
+                code_hash,
+                storage_version: self.storage_version,
+            });
+            Ok(())
+        }
+
+        /// Run by the new code after `upgrade`. Records are migrated lazily as they are
+        /// read; eager steps for a version bump go here.
+        #[ink(message)]
+        pub fn migrate_storage(&mut self) -> Result<()> {
+            self.access.ensure_role(Role::Admin, self.env().caller())?;
+            if self.storage_version >= STORAGE_VERSION {
+                return Err(Error::StorageUpToDate);
+            }
+
+            let from_version = self.storage_version;
+            self.storage_version = STORAGE_VERSION;
+
+            self.env().emit_event(StorageMigrated {
+                from_version,
+                to_version: STORAGE_VERSION,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version
+        }
+
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            Ok(self.access.grant_role(self.env().caller(), role, account)?)
+        }
+
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            Ok(self.access.revoke_role(self.env().caller(), role, account)?)
+        }
+
+        #[ink(message)]
+        pub fn renounce_role(&mut self, role: Role) -> Result<()> {
+            Ok(self.access.renounce_role(self.env().caller(), role)?)
+        }
+
+        #[ink(message)]
+        pub fn set_role_admin(&mut self, role: Role, admin_role: Role) -> Result<()> {
+            Ok(self.access.set_role_admin(self.env().caller(), role, admin_role)?)
+        }
+
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.access.has_role(role, account)
+        }
+
+        #[ink(message)]
+        pub fn get_role_admin(&self, role: Role) -> Role {
+            self.access.role_admin(role)
+        }
+
+        fn open_plate_record(&mut self, vehicle_id: &str, plate: &str, timestamp: u64) {
+            let mut history = self.plate_history.get(vehicle_id).unwrap_or_default();
+            history.push(PlateRecord {
+                plate: String::from(plate),
+                assigned_at: timestamp,
+                released_at: None,
+            });
+            self.plate_history.insert(vehicle_id, &history);
+
+            let mut assignments = self.plate_assignments.get(plate).unwrap_or_default();
+            assignments.push(PlateAssignment {
+                vehicle_id: String::from(vehicle_id),
+                assigned_at: timestamp,
+                released_at: None,
+            });
+            self.plate_assignments.insert(plate, &assignments);
+        }
+
+        fn close_plate_record(&mut self, vehicle_id: &str, plate: &str, timestamp: u64) {
+            let mut history = self.plate_history.get(vehicle_id).unwrap_or_default();
+            if let Some(record) = history.iter_mut().rev().find(|record| record.plate == plate) {
+                record.released_at.get_or_insert(timestamp);
+            }
+            self.plate_history.insert(vehicle_id, &history);
+
+            let mut assignments = self.plate_assignments.get(plate).unwrap_or_default();
+            if let Some(assignment) = assignments
+                .iter_mut()
+                .rev()
+                .find(|assignment| assignment.vehicle_id == vehicle_id)
+            {
+                assignment.released_at.get_or_insert(timestamp);
+            }
+            self.plate_assignments.insert(plate, &assignments);
+        }
+
+        fn managed_sensor(&self, sensor_id: &str, caller: AccountId) -> Result<Sensor> {
+            let sensor = self.sensors.get(sensor_id).ok_or(Error::SensorNotFound)?;
+            if sensor.status == SensorStatus::Retired {
+                return Err(Error::SensorRetired);
+            }
+            let vehicle = self.registered_vehicle(&sensor.vehicle_id)?;
+
+            self.ensure_vehicle_access(&vehicle, caller, OperatorAction::ManageSensors)?;
+
+            Ok(sensor)
+        }
+
+        fn set_sensor_status(&mut self, sensor: &mut Sensor, new_status: SensorStatus) {
+            if sensor.status == SensorStatus::Active && new_status != SensorStatus::Active {
+                self.stats.active_sensors = self.stats.active_sensors.saturating_sub(1);
+            } else if sensor.status != SensorStatus::Active && new_status == SensorStatus::Active {
+                self.stats.active_sensors = self.stats.active_sensors.saturating_add(1);
+            }
+            counter_sub(&mut self.sensor_status_counts, &sensor.status);
+            counter_add(&mut self.sensor_status_counts, &new_status);
+            sensor.status = new_status;
+        }
+
+        /// Detaches a sensor for good. The record stays, marked `Retired`, so its id and
+        /// history cannot be picked up by a new registration.
+        fn retire_sensor(&mut self, mut sensor: Sensor) {
+            if sensor.status == SensorStatus::Active {
+                self.stats.active_sensors = self.stats.active_sensors.saturating_sub(1);
+            }
+            self.stats.total_sensors = self.stats.total_sensors.saturating_sub(1);
+            counter_sub(&mut self.sensor_status_counts, &sensor.status);
+            counter_sub(&mut self.sensor_type_counts, &sensor.sensor_type);
+
+            // The record stays so its id cannot be reused; calibration and installation
+            // history stay with it, but its key no longer verifies attestations.
+            sensor.status = SensorStatus::Retired;
+            self.sensors.insert(&sensor.id, &sensor);
+            self.sensor_keys.remove(&sensor.id);
+            if self.did_sensors.get(&sensor.did_identifier).as_ref() == Some(&sensor.id) {
+                self.did_sensors.remove(&sensor.did_identifier);
+            }
+            self.close_installation(&sensor.id, self.env().block_timestamp());
+        }
+
+        fn open_installation(&mut self, sensor_id: &str, vehicle_id: &str, timestamp: u64) {
+            let mut installations = self.sensor_installations.get(sensor_id).unwrap_or_default();
+            installations.push(InstallationRecord {
+                vehicle_id: String::from(vehicle_id),
+                installed_at: timestamp,
+                removed_at: None,
+            });
+            self.sensor_installations.insert(sensor_id, &installations);
+        }
+
+        fn close_installation(&mut self, sensor_id: &str, timestamp: u64) {
+            let mut installations = self.sensor_installations.get(sensor_id).unwrap_or_default();
+            if let Some(installation) = installations.last_mut() {
+                installation.removed_at.get_or_insert(timestamp);
+            }
+            self.sensor_installations.insert(sensor_id, &installations);
+        }
+
+        fn push_location(&mut self, vehicle_id: &str, location: &Location) {
+            let capacity = self.location_history_capacity;
+            let mut buffer = self.location_buffers.get(vehicle_id).unwrap_or(LocationBuffer {
+                capacity,
+                head: 0,
+                len: 0,
+            });
+
+            if buffer.capacity != capacity {
+                buffer = self.resize_location_buffer(vehicle_id, buffer, capacity);
+            }
+
+            if capacity == 0 {
+                self.location_buffers.remove(vehicle_id);
+                return;
+            }
+
+            self.location_points.insert((vehicle_id, buffer.head), location);
+            buffer.head = (buffer.head + 1) % capacity;
+            buffer.len = buffer.len.saturating_add(1).min(capacity);
+            self.location_buffers.insert(vehicle_id, &buffer);
+        }
+
+        /// Re-lays an existing ring under a new capacity, keeping the newest points.
+        fn resize_location_buffer(
+            &mut self,
+            vehicle_id: &str,
+            buffer: LocationBuffer,
+            capacity: u32,
+        ) -> LocationBuffer {
+            let mut points = Vec::new();
+            for offset in 0..buffer.len {
+                let slot = (buffer.head + buffer.capacity - buffer.len + offset) % buffer.capacity;
+                if let Some(location) = self.location_points.get((vehicle_id, slot)) {
+                    points.push(location);
+                }
+            }
+            for slot in 0..buffer.capacity {
+                self.location_points.remove((vehicle_id, slot));
+            }
+
+            let keep_from = points.len().saturating_sub(capacity as usize);
+            let kept = &points[keep_from..];
+            for (slot, location) in kept.iter().enumerate() {
+                self.location_points.insert((vehicle_id, slot as u32), location);
+            }
+
+            let len = kept.len() as u32;
+            LocationBuffer {
+                capacity,
+                head: if capacity == 0 { 0 } else { len % capacity },
+                len,
+            }
+        }
+
+        fn evaluate_geofences(&mut self, vehicle: &mut Vehicle, location: &Location, caller: AccountId) {
+            let geofence_ids = self.vehicle_geofences.get(&vehicle.id).unwrap_or_default();
+
+            for geofence_id in geofence_ids {
+                let Some(geofence) = self.geofences.get(geofence_id) else {
+                    continue;
+                };
+
+                let inside = geofence.shape.contains(location.latitude, location.longitude);
+                let previous = self.geofence_presence.get((&vehicle.id, geofence_id));
+                if previous == Some(inside) {
+                    continue;
+                }
+                self.geofence_presence.insert((&vehicle.id, geofence_id), &inside);
+
+                if inside {
+                    self.env().emit_event(GeofenceEntered {
+                        vehicle_id: vehicle.id.clone(),
+                        geofence_id,
+                        latitude: location.latitude,
+                        longitude: location.longitude,
+                        timestamp: location.timestamp,
+                    });
+                } else if previous.is_some() {
+                    self.env().emit_event(GeofenceExited {
+                        vehicle_id: vehicle.id.clone(),
+                        geofence_id,
+                        latitude: location.latitude,
+                        longitude: location.longitude,
+                        timestamp: location.timestamp,
+                    });
+                }
+
+                let violated = match geofence.kind {
+                    GeofenceKind::Inclusion => !inside,
+                    GeofenceKind::Exclusion => inside,
+                };
+                if !violated {
+                    continue;
+                }
+
+                let suspend = geofence.suspend_on_violation && vehicle.status != VehicleStatus::Suspended;
+                self.env().emit_event(GeofenceViolation {
+                    vehicle_id: vehicle.id.clone(),
+                    geofence_id,
+                    kind: geofence.kind,
+                    latitude: location.latitude,
+                    longitude: location.longitude,
+                    suspended: suspend,
+                });
+
+                if suspend {
+                    let old_status = vehicle.status.clone();
+                    self.set_vehicle_status(vehicle, VehicleStatus::Suspended);
+                    self.vehicles.insert(&vehicle.id, &*vehicle);
+
+                    self.record_status_change(
+                        &vehicle.id,
+                        old_status,
+                        VehicleStatus::Suspended,
+                        StatusReason::GeofenceViolation,
+                        caller,
+                    );
+                }
+            }
+        }
+
+        fn retire_vehicle(&mut self, mut vehicle: Vehicle, caller: AccountId, reason: StatusReason) {
+            let timestamp = self.env().block_timestamp();
+            let old_status = vehicle.status.clone();
+
+            self.vin_to_id.remove(&vehicle.vin);
+            self.license_to_id.remove(&vehicle.license_plate);
+            self.close_plate_record(&vehicle.id, &vehicle.license_plate, timestamp);
+            self.retired_vins.insert(&vehicle.vin, &vehicle.id);
+            if self.did_vehicles.get(&vehicle.did_identifier).as_ref() == Some(&vehicle.id) {
+                self.did_vehicles.remove(&vehicle.did_identifier);
+            }
+            self.pending_transfers.remove(&vehicle.id);
+            self.service_visits.remove(&vehicle.id);
+
+            self.remove_owner_vehicle(vehicle.owner, &vehicle.id);
+
+            self.clear_operator(&mut vehicle);
+
+            let sensor_ids = self.vehicle_sensor_ids(&vehicle.id);
+            for sensor_id in sensor_ids.iter() {
+                self.remove_vehicle_sensor(&vehicle.id, sensor_id);
+                if let Some(sensor) = self.sensors.get(sensor_id) {
+                    self.retire_sensor(sensor);
+                }
+            }
+
+            let mut history = self.ownership_history.get(&vehicle.id).unwrap_or_default();
+            let acquired_at = history
+                .last()
+                .map_or(vehicle.registered_at, |record| record.released_at.max(vehicle.registered_at));
+            history.push(OwnershipRecord {
+                owner: vehicle.owner,
+                acquired_at,
+                released_at: timestamp,
+            });
+            self.ownership_history.insert(&vehicle.id, &history);
+
+            self.stats.total_vehicles = self.stats.total_vehicles.saturating_sub(1);
+            if vehicle.engine_type == EngineType::Electric {
+                self.stats.electric_vehicles = self.stats.electric_vehicles.saturating_sub(1);
+            }
+            counter_sub(&mut self.engine_type_counts, &vehicle.engine_type);
+
+            self.set_vehicle_status(&mut vehicle, VehicleStatus::Deregistered);
+            vehicle.last_updated = timestamp;
+            self.vehicles.insert(&vehicle.id, &vehicle);
+            self.record_status_change(&vehicle.id, old_status, VehicleStatus::Deregistered, reason, caller);
+
+            self.env().emit_event(VehicleDeregistered {
+                vehicle_id: vehicle.id,
+                owner: vehicle.owner,
+                vin: vehicle.vin,
+                retired_sensors: sensor_ids.len().min(u32::MAX as usize) as u32,
+                deregistered_by: caller,
+            });
+        }
+
+        fn sync_vehicle_did(&mut self, vehicle_id: &str) -> Result<bool> {
+            let vehicle = self.registered_vehicle(vehicle_id)?;
+            let address = self.get_did_registry().ok_or(Error::DidRegistryNotConfigured)?;
+            let did_registry: DidRegistryRef = ink::env::call::FromAccountId::from_account_id(address);
+
+            let document = did_registry
+                .call()
+                .get_did(vehicle.did_identifier.clone())
+                .try_invoke()
+                .map_err(|_| Error::DidRegistryCallFailed)?
+                .map_err(|_| Error::DidRegistryCallFailed)?
+                .ok_or(Error::DidNotFound)?;
+
+            Ok(self.apply_did_status(vehicle, document.status))
+        }
+
+        fn apply_did_status(&mut self, mut vehicle: Vehicle, did_status: DidStatus) -> bool {
+            let caller = self.env().caller();
+            let old_status = vehicle.status.clone();
+
+            match did_status {
+                DidStatus::Active => return false,
+                DidStatus::Suspended if old_status == VehicleStatus::Suspended => return false,
+                DidStatus::Suspended => {}
+                DidStatus::Revoked => {
+                    self.retire_vehicle(vehicle, caller, StatusReason::DidRevoked);
+                    return true;
+                }
+            }
+
+            let sensor_ids = self.vehicle_sensor_ids(&vehicle.id);
+            for sensor_id in sensor_ids {
+                let Some(mut sensor) = self.sensors.get(&sensor_id) else {
+                    continue;
+                };
+                if sensor.status != SensorStatus::Active {
+                    continue;
+                }
+                self.set_sensor_status(&mut sensor, SensorStatus::Inactive);
+                self.sensors.insert(&sensor_id, &sensor);
+                self.env().emit_event(SensorStatusChanged {
+                    sensor_id,
+                    vehicle_id: vehicle.id.clone(),
+                    old_status: SensorStatus::Active,
+                    new_status: SensorStatus::Inactive,
+                    changed_by: caller,
+                });
+            }
+
+            self.set_vehicle_status(&mut vehicle, VehicleStatus::Suspended);
+            vehicle.last_updated = self.env().block_timestamp();
+            self.vehicles.insert(&vehicle.id, &vehicle);
+
+            self.record_status_change(
+                &vehicle.id,
+                old_status,
+                VehicleStatus::Suspended,
+                StatusReason::DidSuspended,
+                caller,
+            );
+            true
+        }
+
+        fn record_status_change(
+            &mut self,
+            vehicle_id: &str,
+            old_status: VehicleStatus,
+            new_status: VehicleStatus,
+            reason: StatusReason,
+            changed_by: AccountId,
+        ) {
+            let index = self.status_record_count.get(vehicle_id).unwrap_or(0);
+            self.status_records.insert(
+                (vehicle_id, index),
+                &StatusRecord {
+                    from: old_status.clone(),
+                    to: new_status.clone(),
+                    reason,
+                    changed_by,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            self.status_record_count.insert(vehicle_id, &index.saturating_add(1));
+
+            self.env().emit_event(VehicleStatusChanged {
+                vehicle_id: String::from(vehicle_id),
+                old_status,
+                new_status,
+                changed_by,
+                reason,
+            });
+        }
+
+        fn set_vehicle_status(&mut self, vehicle: &mut Vehicle, new_status: VehicleStatus) {
+            if vehicle.status == VehicleStatus::Active && new_status != VehicleStatus::Active {
+                self.stats.active_vehicles = self.stats.active_vehicles.saturating_sub(1);
+            } else if vehicle.status != VehicleStatus::Active && new_status == VehicleStatus::Active {
+                self.stats.active_vehicles = self.stats.active_vehicles.saturating_add(1);
+            }
+            counter_sub(&mut self.vehicle_status_counts, &vehicle.status);
+            counter_add(&mut self.vehicle_status_counts, &new_status);
+            vehicle.status = new_status;
+        }
+
+        fn verify_mileage_attestation(
+            &self,
+            vehicle_id: &str,
+            nonce: u32,
+            previous_mileage: u32,
+            mileage: u32,
+            attestation: &MileageAttestation,
+        ) -> Result<()> {
+            let sensor = self.sensors.get(&attestation.sensor_id).ok_or(Error::SensorNotFound)?;
+            if sensor.vehicle_id != vehicle_id
+                || sensor.sensor_type != SensorType::Odometer
+                || sensor.status != SensorStatus::Active
+            {
+                return Err(Error::InvalidAttestation);
+            }
+
+            let public_key = self.sensor_keys.get(&attestation.sensor_id).ok_or(Error::SensorKeyMissing)?;
+            let message =
+                mileage_attestation_message(self.env().account_id(), vehicle_id, nonce, previous_mileage, mileage);
+
+            self.env()
+                .sr25519_verify(&attestation.signature, &message, &public_key)
+                .map_err(|_| Error::InvalidAttestation)
+        }
+
+        fn check_vehicle_registration(&self, registration: &VehicleRegistration) -> Result<()> {
+            let previous = self.vehicles.get(&registration.vehicle_id);
+            if let Some(ref existing) = previous {
+                if existing.status != VehicleStatus::Deregistered {
+                    return Err(Error::VehicleAlreadyRegistered);
+                }
+            }
+
+            if self.vin_to_id.contains(&registration.vin) {
+                return Err(Error::DuplicateVin);
+            }
+
+            if self.license_to_id.contains(&registration.license_plate) {
+                return Err(Error::DuplicateLicensePlate);
+            }
+
+            if registration.vehicle_id.is_empty() || registration.vin.is_empty() || registration.make.is_empty() {
+                return Err(Error::InvalidVehicleData);
+            }
+
+            self.validate_vin(registration)?;
+
+            // A deregistered VIN may only come back under its original record, with make
+            // and year matching the physical vehicle. Reviving it is a status transition
+            // like any other; the record keeps its last owner and odometer reading.
+            let caller = self.env().caller();
+            let mut owner = caller;
+            if let Some(retired_id) = self.retired_vins.get(&registration.vin) {
+                let existing = previous
+                    .filter(|existing| {
+                        existing.id == retired_id
+                            && existing.make == registration.make
+                            && existing.year == registration.year
+                    })
+                    .ok_or(Error::RetiredVin)?;
+                let actor = if self.access.has_role(Role::Registrar, caller) {
+                    StatusActor::Registrar
+                } else if existing.owner == caller {
+                    StatusActor::Owner
+                } else {
+                    StatusActor::Operator
+                };
+                check_status_transition(&existing.status, &VehicleStatus::Active, actor)?;
+                owner = existing.owner;
+            } else if previous.is_some() {
+                return Err(Error::VehicleAlreadyRegistered);
+            }
+
+            // DIDs are only resolved, and only held unique, against a configured registry.
+            if self.get_did_registry().is_none() {
+                return Ok(());
+            }
+            if self
+                .did_vehicles
+                .get(&registration.did_identifier)
+                .is_some_and(|bound| bound != registration.vehicle_id)
+            {
+                return Err(Error::DidAlreadyBound);
+            }
+            self.verify_did(&registration.did_identifier, EntityType::Vehicle, owner)
+        }
+
+        fn validate_vin(&self, registration: &VehicleRegistration) -> Result<()> {
+            let vin = registration.vin.as_bytes();
+            if vin.len() != VIN_LENGTH {
+                return Err(Error::InvalidVinLength);
+            }
+            if vin.iter().any(|character| vin_value(*character).is_none()) {
+                return Err(Error::InvalidVinCharacter);
+            }
+
+            let region = vin_region(vin);
+            if self.is_vin_check_digit_required(region) && vin_check_digit(vin) != Some(vin[VIN_CHECK_DIGIT_POSITION]) {
+                return Err(Error::InvalidVinCheckDigit);
+            }
+
+            if self.is_vin_model_year_required(region)
+                && !vin_model_year_matches(vin[VIN_MODEL_YEAR_POSITION], registration.year)?
+            {
+                return Err(Error::VinYearMismatch);
+            }
+
+            if let Some(make) = self.wmi_manufacturers.get(&registration.vin[..3]) {
+                if !make.eq_ignore_ascii_case(&registration.make) {
+                    return Err(Error::VinManufacturerMismatch);
+                }
+            }
+
+            Ok(())
+        }
+
+        fn insert_vehicle(&mut self, registration: VehicleRegistration) {
+            let timestamp = self.env().block_timestamp();
+
+            // Deregistered records are kept, so a re-registration is already indexed.
+            let previous = self.vehicles.get(&registration.vehicle_id);
+            let reregistered = previous.is_some();
+            if !reregistered {
+                self.vehicle_ids.insert(self.vehicle_id_count, &registration.vehicle_id);
+                self.vehicle_id_count = self.vehicle_id_count.saturating_add(1);
+            }
+            let (owner, mileage) = previous.map_or((self.env().caller(), 0), |existing| (existing.owner, existing.mileage));
+
+            let vehicle = Vehicle {
+                id: registration.vehicle_id.clone(),
+                did_identifier: registration.did_identifier,
+                owner,
+                operator: None,
+                vin: registration.vin.clone(),
+                make: registration.make.clone(),
+                model: registration.model.clone(),
+                year: registration.year,
+                license_plate: registration.license_plate.clone(),
+                engine_type: registration.engine_type.clone(),
+                battery_capacity: registration.battery_capacity,
+                status: VehicleStatus::Active,
+                registered_at: timestamp,
+                last_updated: timestamp,
+                mileage,
+                location: None,
+            };
+
+            self.vehicles.insert(&registration.vehicle_id, &vehicle);
+            if !vehicle.did_identifier.is_empty() {
+                self.did_vehicles.insert(&vehicle.did_identifier, &registration.vehicle_id);
+            }
+            self.retired_vins.remove(&registration.vin);
+            self.vin_to_id.insert(&registration.vin, &registration.vehicle_id);
+            self.license_to_id.insert(&registration.license_plate, &registration.vehicle_id);
+            self.open_plate_record(&registration.vehicle_id, &registration.license_plate, timestamp);
+
+            self.add_owner_vehicle(owner, &registration.vehicle_id);
+
+            self.stats.total_vehicles = self.stats.total_vehicles.saturating_add(1);
+            self.stats.active_vehicles = self.stats.active_vehicles.saturating_add(1);
+            
+            if registration.engine_type == EngineType::Electric {
+                self.stats.electric_vehicles = self.stats.electric_vehicles.saturating_add(1);
+            }
+            if reregistered {
+                counter_sub(&mut self.vehicle_status_counts, &VehicleStatus::Deregistered);
+            }
+            counter_add(&mut self.vehicle_status_counts, &VehicleStatus::Active);
+            counter_add(&mut self.engine_type_counts, &registration.engine_type);
+
+            if reregistered {
+                self.record_status_change(
+                    &registration.vehicle_id,
+                    VehicleStatus::Deregistered,
+                    VehicleStatus::Active,
+                    StatusReason::Reregistered,
+                    self.env().caller(),
+                );
+            }
+
+            self.env().emit_event(VehicleRegistered {
+                vehicle_id: registration.vehicle_id,
+                owner,
+                vin: registration.vin,
+                make: registration.make,
+                model: registration.model,
+            });
+        }
+
+        fn check_sensor_registration(&self, registration: &SensorRegistration, caller: AccountId) -> Result<()> {
+            let vehicle = self.registered_vehicle(&registration.vehicle_id)?;
+
+            if self.sensors.contains(&registration.sensor_id) {
+                return Err(Error::SensorAlreadyRegistered);
+            }
+
+            self.ensure_vehicle_access(&vehicle, caller, OperatorAction::ManageSensors)?;
+            self.ensure_sensor_capacity(&registration.vehicle_id, 0)?;
+
+            if self.get_did_registry().is_none() {
+                return Ok(());
+            }
+            if self.did_sensors.contains(&registration.did_identifier) {
+                return Err(Error::DidAlreadyBound);
+            }
+            self.verify_did(&registration.did_identifier, EntityType::Sensor, caller)
+        }
+
+        fn verify_did(&self, did_identifier: &str, entity_type: EntityType, caller: AccountId) -> Result<()> {
+            let Some(address) = self.get_did_registry() else {
+                return Ok(());
+            };
+            let did_registry: DidRegistryRef = ink::env::call::FromAccountId::from_account_id(address);
+
+            let document = did_registry
+                .call()
+                .get_did(String::from(did_identifier))
+                .try_invoke()
+                .map_err(|_| Error::DidRegistryCallFailed)?
+                .map_err(|_| Error::DidRegistryCallFailed)?
+                .ok_or(Error::DidNotFound)?;
+
+            if document.status != DidStatus::Active {
+                return Err(Error::DidNotActive);
+            }
+            if document.entity_type != entity_type {
+                return Err(Error::DidEntityMismatch);
+            }
+
+            if document.controller != caller {
+                let has_access = did_registry
+                    .call()
+                    .verify_access(String::from(did_identifier), caller)
+                    .try_invoke()
+                    .map_err(|_| Error::DidRegistryCallFailed)?
+                    .map_err(|_| Error::DidRegistryCallFailed)?;
+                if !has_access {
+                    return Err(Error::DidNotControlled);
+                }
+            }
+
+            Ok(())
+        }
+
+        fn insert_sensor(&mut self, registration: SensorRegistration, caller: AccountId) {
+            let timestamp = self.env().block_timestamp();
+
+            let sensor = Sensor {
+                id: registration.sensor_id.clone(),
+                did_identifier: registration.did_identifier,
+                vehicle_id: registration.vehicle_id.clone(),
+                sensor_type: registration.sensor_type.clone(),
+                manufacturer: registration.manufacturer,
+                model: registration.model,
+                status: SensorStatus::Active,
+                installed_at: timestamp,
+                last_calibrated: timestamp,
+                accuracy: registration.accuracy,
+            };
+
+            self.sensors.insert(&registration.sensor_id, &sensor);
+            if !sensor.did_identifier.is_empty() {
+                self.did_sensors.insert(&sensor.did_identifier, &registration.sensor_id);
+            }
+            self.open_installation(&registration.sensor_id, &registration.vehicle_id, timestamp);
+
+            self.add_vehicle_sensor(&registration.vehicle_id, &registration.sensor_id);
+
+            self.stats.total_sensors = self.stats.total_sensors.saturating_add(1);
+            self.stats.active_sensors = self.stats.active_sensors.saturating_add(1);
+            counter_add(&mut self.sensor_status_counts, &SensorStatus::Active);
+            counter_add(&mut self.sensor_type_counts, &sensor.sensor_type);
+
+            self.env().emit_event(SensorRegistered {
+                sensor_id: registration.sensor_id,
+                vehicle_id: registration.vehicle_id,
+                sensor_type: registration.sensor_type,
+                registered_by: caller,
+            });
+        }
+
+        /// Gates every query that pages or returns a whole list; auditors keep read access
+        /// while those are paused.
+        fn ensure_queries_allowed(&self) -> Result<()> {
+            if self.access.has_role(Role::Auditor, self.env().caller()) {
+                return Ok(());
+            }
+            Ok(self.pausable.get_or_default().ensure_not_paused(PauseScope::Query)?)
+        }
+
+        fn release_vehicles_of(&mut self, operator: AccountId, limit: u32, caller: AccountId) {
+            let timestamp = self.env().block_timestamp();
+            for _ in 0..limit {
+                let Some(vehicle_id) = self
+                    .operator_vehicle_count
+                    .get(operator)
+                    .and_then(|count| count.checked_sub(1))
+                    .and_then(|last| self.operator_vehicles.get((operator, last)))
+                else {
+                    break;
+                };
+                let Some(mut vehicle) = self.vehicles.get(&vehicle_id) else {
+                    self.remove_operator_vehicle(operator, &vehicle_id);
+                    continue;
+                };
+                self.unassign(&mut vehicle, caller);
+                vehicle.last_updated = timestamp;
+                self.vehicles.insert(&vehicle_id, &vehicle);
+            }
+        }
+
+        fn ensure_batch_size(&self, len: usize) -> Result<()> {
+            if len > self.get_max_batch_size() as usize {
+                return Err(Error::BatchTooLarge);
+            }
+            Ok(())
+        }
+
+        fn ensure_vehicle_access(&self, vehicle: &Vehicle, caller: AccountId, action: OperatorAction) -> Result<()> {
+            if vehicle.owner == caller || self.access.has_role(Role::FleetManager, caller) {
+                return Ok(());
+            }
+
+            if vehicle.operator != Some(caller) {
+                return Err(Error::Unauthorized);
+            }
+            if !self.authorized_operators.get(caller).unwrap_or(false) {
+                return Err(Error::OperatorNotAuthorized);
+            }
+
+            let assignment = self.operator_assignments.get(&vehicle.id).ok_or(Error::Unauthorized)?;
+            let now = self.env().block_timestamp();
+            if assignment.expires_at.is_some_and(|expires_at| expires_at <= now) {
+                return Err(Error::OperatorExpired);
+            }
+
+            let permitted = match action {
+                OperatorAction::UpdateLocation => assignment.permissions.can_update_location,
+                OperatorAction::ChangeStatus => assignment.permissions.can_change_status,
+                OperatorAction::ManageSensors => assignment.permissions.can_register_sensors,
+                OperatorAction::UpdateMileage => assignment.permissions.can_update_mileage,
+            };
+            if !permitted {
+                return Err(Error::Unauthorized);
+            }
+
+            Ok(())
+        }
+
+        /// Drops the current operator, if any, from the vehicle and its indexes.
+        /// The caller is responsible for persisting `vehicle`.
+        fn clear_operator(&mut self, vehicle: &mut Vehicle) -> Option<AccountId> {
+            let operator = vehicle.operator.take()?;
+            self.remove_operator_vehicle(operator, &vehicle.id);
+            self.operator_assignments.remove(&vehicle.id);
+            Some(operator)
+        }
+
+        fn unassign(&mut self, vehicle: &mut Vehicle, unassigned_by: AccountId) -> Option<AccountId> {
+            let operator = self.clear_operator(vehicle)?;
+            self.env().emit_event(OperatorUnassigned {
+                vehicle_id: vehicle.id.clone(),
+                operator,
+                unassigned_by,
+            });
+            Some(operator)
+        }
+
+        fn lapse_expired_operator(&mut self, vehicle: &mut Vehicle) -> bool {
+            let now = self.env().block_timestamp();
+            let Some(expired_at) = self
+                .operator_assignments
+                .get(&vehicle.id)
+                .and_then(|assignment| assignment.expires_at)
+                .filter(|expires_at| *expires_at <= now)
+            else {
+                return false;
+            };
+
+            if let Some(operator) = self.clear_operator(vehicle) {
+                self.env().emit_event(OperatorExpired {
+                    vehicle_id: vehicle.id.clone(),
+                    operator,
+                    expired_at,
+                });
+            }
+            true
+        }
+
+        fn add_owner_vehicle(&mut self, owner: AccountId, vehicle_id: &str) {
+            index_push(
+                &mut self.owner_vehicle_count,
+                &mut self.owner_vehicles,
+                &mut self.owner_vehicle_index,
+                owner,
+                vehicle_id,
+            );
+        }
+
+        fn remove_owner_vehicle(&mut self, owner: AccountId, vehicle_id: &str) {
+            index_remove(
+                &mut self.owner_vehicle_count,
+                &mut self.owner_vehicles,
+                &mut self.owner_vehicle_index,
+                owner,
+                vehicle_id,
+            );
+        }
+
+        fn add_operator_vehicle(&mut self, operator: AccountId, vehicle_id: &str) {
+            index_push(
+                &mut self.operator_vehicle_count,
+                &mut self.operator_vehicles,
+                &mut self.operator_vehicle_index,
+                operator,
+                vehicle_id,
+            );
+        }
+
+        fn remove_operator_vehicle(&mut self, operator: AccountId, vehicle_id: &str) {
+            index_remove(
+                &mut self.operator_vehicle_count,
+                &mut self.operator_vehicles,
+                &mut self.operator_vehicle_index,
+                operator,
+                vehicle_id,
+            );
+        }
+
+        fn vehicle_sensor_ids(&self, vehicle_id: &str) -> Vec<String> {
+            let vehicle_id = String::from(vehicle_id);
+            index_page(&self.vehicle_sensor_count, &self.vehicle_sensors, &vehicle_id, 0, MAX_SENSORS_PER_VEHICLE)
+        }
+
+        fn ensure_sensor_capacity(&self, vehicle_id: &str, queued: u32) -> Result<()> {
+            let installed = self.vehicle_sensor_count.get(vehicle_id).unwrap_or(0);
+            if installed.saturating_add(queued) >= MAX_SENSORS_PER_VEHICLE {
+                return Err(Error::SensorLimitReached);
+            }
+            Ok(())
+        }
+
+        fn add_vehicle_sensor(&mut self, vehicle_id: &str, sensor_id: &str) {
+            index_push(
+                &mut self.vehicle_sensor_count,
+                &mut self.vehicle_sensors,
+                &mut self.vehicle_sensor_index,
+                String::from(vehicle_id),
+                sensor_id,
+            );
+        }
+
+        fn remove_vehicle_sensor(&mut self, vehicle_id: &str, sensor_id: &str) {
+            index_remove(
+                &mut self.vehicle_sensor_count,
+                &mut self.vehicle_sensors,
+                &mut self.vehicle_sensor_index,
+                String::from(vehicle_id),
+                sensor_id,
+            );
+        }
+
+        fn registered_vehicle(&self, vehicle_id: &str) -> Result<Vehicle> {
+            let vehicle = self.vehicles.get(vehicle_id).ok_or(Error::VehicleNotFound)?;
+            if vehicle.status == VehicleStatus::Deregistered {
+                return Err(Error::VehicleDeregistered);
+            }
+            Ok(vehicle)
+        }
+    }
+
+    impl GeofenceShape {
+        pub fn is_valid(&self) -> bool {
+            match self {
+                GeofenceShape::Circle {
+                    latitude,
+                    longitude,
+                    radius_m,
+                } => *radius_m > 0 && is_valid_coordinate(*latitude, *longitude),
+                GeofenceShape::Polygon { vertices } => {
+                    (3..=MAX_GEOFENCE_VERTICES).contains(&vertices.len())
+                        && vertices.iter().all(|(lat, lon)| is_valid_coordinate(*lat, *lon))
+                }
+            }
+        }
+
+        pub fn contains(&self, latitude: i32, longitude: i32) -> bool {
+            match self {
+                GeofenceShape::Circle {
+                    latitude: center_lat,
+                    longitude: center_lon,
+                    radius_m,
+                } => {
+                    let radius = (*radius_m as i128).saturating_mul(1_000_000);
+                    ground_distance_squared(*center_lat, *center_lon, latitude, longitude)
+                        <= radius.saturating_mul(radius)
+                }
+                GeofenceShape::Polygon { vertices } => polygon_contains(vertices, latitude, longitude),
+            }
+        }
+    }
+
+    impl OperatorPermissions {
+        pub fn all() -> Self {
+            Self {
+                can_update_location: true,
+                can_change_status: true,
+                can_register_sensors: true,
+                can_update_mileage: true,
+            }
+        }
+    }
+
+    impl VehicleFilter {
+        fn matches(&self, vehicle: &Vehicle) -> bool {
+            self.status.as_ref().is_none_or(|status| *status == vehicle.status)
+                && self.engine_type.as_ref().is_none_or(|engine_type| *engine_type == vehicle.engine_type)
+                && self.make.as_ref().is_none_or(|make| *make == vehicle.make)
+                && self.model.as_ref().is_none_or(|model| *model == vehicle.model)
+                && self.min_year.is_none_or(|min_year| vehicle.year >= min_year)
+                && self.max_year.is_none_or(|max_year| vehicle.year <= max_year)
+        }
+    }
+
+    // Enumerable one-to-many indexes are kept as a per-key counter, `(key, position) -> id`
+    // entries and an `id -> position` back-reference, so removal is a swap with the last entry.
+    // Every id belongs to at most one key of a given index.
+
+    fn index_push<K, C, I, P>(
+        counts: &mut Mapping<K, u32, C>,
+        ids: &mut Mapping<(K, u32), String, I>,
+        positions: &mut Mapping<String, u32, P>,
+        key: K,
+        id: &str,
+    ) where
+        K: EncodeLike + Clone,
+        C: StorageKey,
+        I: StorageKey,
+        P: StorageKey,
+    {
+        let count = counts.get(&key).unwrap_or(0);
+        ids.insert((key.clone(), count), &String::from(id));
+        positions.insert(id, &count);
+        counts.insert(key, &count.saturating_add(1));
+    }
+
+    fn index_remove<K, C, I, P>(
+        counts: &mut Mapping<K, u32, C>,
+        ids: &mut Mapping<(K, u32), String, I>,
+        positions: &mut Mapping<String, u32, P>,
+        key: K,
+        id: &str,
+    ) where
+        K: EncodeLike + Clone,
+        C: StorageKey,
+        I: StorageKey,
+        P: StorageKey,
+    {
+        let Some(position) = positions.get(id) else {
+            return;
+        };
+        let last = counts.get(&key).unwrap_or(0).saturating_sub(1);
+
+        if position != last {
+            if let Some(last_id) = ids.get((key.clone(), last)) {
+                ids.insert((key.clone(), position), &last_id);
+                positions.insert(&last_id, &position);
+            }
+        }
+
+        ids.remove((key.clone(), last));
+        positions.remove(id);
+        counts.insert(key, &last);
+    }
+
+    fn index_page<K, C, I>(
+        counts: &Mapping<K, u32, C>,
+        ids: &Mapping<(K, u32), String, I>,
+        key: &K,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<String>
+    where
+        K: EncodeLike + Clone,
+        C: StorageKey,
+        I: StorageKey,
+    {
+        let count = counts.get(key).unwrap_or(0);
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+        (offset..end)
+            .filter_map(|position| ids.get((key.clone(), position)))
+            .collect()
+    }
+
+    fn counter_add<K: Encode, C: StorageKey>(counters: &mut Mapping<K, u32, C>, key: &K) {
+        let count = counters.get(key).unwrap_or(0);
+        counters.insert(key, &count.saturating_add(1));
+    }
+
+    fn counter_sub<K: Encode, C: StorageKey>(counters: &mut Mapping<K, u32, C>, key: &K) {
+        let count = counters.get(key).unwrap_or(0);
+        counters.insert(key, &count.saturating_sub(1));
+    }
+
+    fn counter_breakdown<K: Encode, C: StorageKey, const N: usize>(
+        counters: &Mapping<K, u32, C>,
+        keys: [K; N],
+    ) -> Vec<(K, u32)> {
+        keys.into_iter()
+            .map(|key| {
+                let count = counters.get(&key).unwrap_or(0);
+                (key, count)
+            })
+            .collect()
+    }
+
+    /// Who may move a vehicle between two statuses by hand. Service visits, geofence
+    /// violations and DID sync change status on their own and are not bound by this table.
+    fn check_status_transition(from: &VehicleStatus, to: &VehicleStatus, actor: StatusActor) -> Result<()> {
+        use StatusActor::*;
+
+        let allowed: &[StatusActor] = match (from, to) {
+            _ if from == to => &[],
+            (VehicleStatus::Deregistered, VehicleStatus::Active) => &[Owner, Registrar],
+            (VehicleStatus::Deregistered, _) => &[],
+            (VehicleStatus::Suspended, VehicleStatus::Deregistered) => &[Registrar],
+            (_, VehicleStatus::Deregistered) => &[Owner, Registrar],
+            (VehicleStatus::Suspended, _) => &[FleetManager],
+            (_, VehicleStatus::Suspended) => &[Owner, FleetManager],
+            _ => &[Owner, Operator, FleetManager],
+        };
+
+        if allowed.is_empty() {
+            return Err(Error::InvalidStatusTransition);
+        }
+        if !allowed.contains(&actor) {
+            return Err(Error::StatusTransitionNotPermitted);
+        }
+        Ok(())
+    }
+
+    fn check_status_reason(to: &VehicleStatus, reason: StatusReason) -> Result<()> {
+        if reason.is_automatic() {
+            return Err(Error::InvalidStatusReason);
+        }
+        if matches!(to, VehicleStatus::Suspended | VehicleStatus::Deregistered) && reason == StatusReason::Manual {
+            return Err(Error::StatusReasonRequired);
+        }
+        Ok(())
+    }
+
+    const VIN_LENGTH: usize = 17;
+    const VIN_CHECK_DIGIT_POSITION: usize = 8;
+    const VIN_MODEL_YEAR_POSITION: usize = 9;
+    const VIN_WEIGHTS: [u32; VIN_LENGTH] = [8, 7, 6, 5, 4, 3, 2, 10, 0, 9, 8, 7, 6, 5, 4, 3, 2];
+    /// Model-year codes repeat every 30 years, starting from 1980.
+    const VIN_MODEL_YEAR_CODES: &[u8; 30] = b"ABCDEFGHJKLMNPRSTVWXY123456789";
+
+    /// ISO 3779 transliteration; `None` for characters a VIN may not contain.
+    fn vin_value(character: u8) -> Option<u32> {
+        let value = match character {
+            b'0'..=b'9' => character - b'0',
+            b'A'..=b'H' => character - b'A' + 1,
+            b'J'..=b'N' => character - b'J' + 1,
+            b'P' => 7,
+            b'R' => 9,
+            b'S'..=b'Z' => character - b'S' + 2,
+            _ => return None,
+        };
+        Some(u32::from(value))
+    }
+
+    fn vin_check_digit(vin: &[u8]) -> Option<u8> {
+        let mut sum = 0u32;
+        for (character, weight) in vin.iter().zip(VIN_WEIGHTS) {
+            sum = sum.saturating_add(vin_value(*character)?.saturating_mul(weight));
+        }
+        Some(match sum % 11 {
+            10 => b'X',
+            remainder => b'0' + remainder as u8,
+        })
+    }
+
+    fn vin_region(vin: &[u8]) -> VinRegion {
+        match vin.first().copied().unwrap_or_default() {
+            b'1'..=b'5' => VinRegion::NorthAmerica,
+            b'6' | b'7' => VinRegion::Oceania,
+            b'8' | b'9' | b'0' => VinRegion::SouthAmerica,
+            b'A'..=b'H' => VinRegion::Africa,
+            b'J'..=b'R' => VinRegion::Asia,
+            _ => VinRegion::Europe,
+        }
+    }
+
+    fn vin_model_year_matches(code: u8, year: u16) -> Result<bool> {
+        let offset = VIN_MODEL_YEAR_CODES
+            .iter()
+            .position(|candidate| *candidate == code)
+            .ok_or(Error::InvalidVinModelYear)?;
+        let base = 1980 + offset as u16;
+        Ok(year >= base && (year - base).is_multiple_of(30))
+    }
+
+    /// The payload an odometer sensor signs for `update_mileage`.
+    /// The nonce and previous reading tie a signature to one state of the odometer log,
+    /// so it cannot be replayed once the log has moved on.
+    fn mileage_attestation_message(
+        contract: AccountId,
+        vehicle_id: &str,
+        nonce: u32,
+        previous_mileage: u32,
+        mileage: u32,
+    ) -> Vec<u8> {
+        (contract, vehicle_id, nonce, previous_mileage, mileage).encode()
+    }
+
+    fn is_valid_coordinate(latitude: i32, longitude: i32) -> bool {
+        (-MAX_LATITUDE..=MAX_LATITUDE).contains(&latitude)
+            && (-MAX_LONGITUDE..=MAX_LONGITUDE).contains(&longitude)
+    }
+
+    /// Cosine of a micro-degree latitude scaled by 1e6, using Bhaskara I's approximation
+    /// (absolute error below 0.002) so it stays in integer arithmetic.
+    fn scaled_cos(latitude: i32) -> i128 {
+        let degrees_squared = (latitude as i128) * (latitude as i128);
+        let half_turn_squared: i128 = 32_400 * 1_000_000_000_000; // 180° in micro-degrees, squared
+        (half_turn_squared - 4 * degrees_squared) * 1_000_000 / (half_turn_squared + degrees_squared)
+    }
+
+    /// Squared equirectangular distance between two fixes, in square micrometres.
+    fn ground_distance_squared(lat_a: i32, lon_a: i32, lat_b: i32, lon_b: i32) -> i128 {
+        let mut dlon = (lon_b as i128) - (lon_a as i128);
+        if dlon > MAX_LONGITUDE as i128 {
+            dlon -= 2 * MAX_LONGITUDE as i128;
+        } else if dlon < -(MAX_LONGITUDE as i128) {
+            dlon += 2 * MAX_LONGITUDE as i128;
+        }
+        let dlat = (lat_b as i128) - (lat_a as i128);
+        let mean_lat = ((lat_a as i128 + lat_b as i128) / 2) as i32;
+
+        let y = dlat * MICROMETRES_PER_MICRODEGREE;
+        let x = dlon * MICROMETRES_PER_MICRODEGREE * scaled_cos(mean_lat) / 1_000_000;
+        x * x + y * y
+    }
+
+    /// Whether a vehicle could have covered the distance from `previous` by `timestamp`
+    /// (milliseconds) without exceeding `max_speed_kmh`.
+    fn within_speed_limit(
+        previous: &Location,
+        latitude: i32,
+        longitude: i32,
+        timestamp: u64,
+        max_speed_kmh: u32,
+    ) -> bool {
+        if max_speed_kmh == 0 {
+            return true;
+        }
+
+        let elapsed_ms = timestamp.saturating_sub(previous.timestamp) as i128;
+        // 1 km/h sustained for 1 ms covers 2500/9 micrometres; scale the distance by 9 instead
+        let reach = (max_speed_kmh as i128).saturating_mul(elapsed_ms).saturating_mul(2_500);
+        let distance_squared = ground_distance_squared(previous.latitude, previous.longitude, latitude, longitude);
+
+        distance_squared.saturating_mul(81) <= reach.saturating_mul(reach)
+    }
+
+    /// Even-odd ray casting over (latitude, longitude) vertices.
+    fn polygon_contains(vertices: &[(i32, i32)], latitude: i32, longitude: i32) -> bool {
+        let (py, px) = (latitude as i128, longitude as i128);
+        let mut inside = false;
+        let mut j = vertices.len().saturating_sub(1);
+
+        for i in 0..vertices.len() {
+            let (yi, xi) = (vertices[i].0 as i128, vertices[i].1 as i128);
+            let (yj, xj) = (vertices[j].0 as i128, vertices[j].1 as i128);
+
+            if (yi > py) != (yj > py) {
+                // px < xi + (py - yi) * (xj - xi) / (yj - yi), without the division
+                let lhs = (px - xi) * (yj - yi);
+                let rhs = (py - yi) * (xj - xi);
+                let crosses = if yj > yi { lhs < rhs } else { lhs > rhs };
+                if crosses {
+                    inside = !inside;
+                }
+            }
+            j = i;
+        }
+
+        inside
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn registration(vehicle_id: &str, vin: &str, license_plate: &str) -> VehicleRegistration {
+            VehicleRegistration {
+                vehicle_id: String::from(vehicle_id),
+                did_identifier: format!("did:peaq:vehicle:{}", vehicle_id.to_lowercase()),
+                vin: String::from(vin),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from(license_plate),
+                engine_type: EngineType::Electric,
+                battery_capacity: Some(75),
+            }
+        }
+
+        /// A valid VIN with a Tesla WMI for the given model year.
+        fn vin_for_year(serial: u32, year: u16) -> String {
+            let code = VIN_MODEL_YEAR_CODES[usize::from(year - 1980) % 30] as char;
+            let mut vin = format!("5YJ3E1EA0{code}F{serial:06}").into_bytes();
+            vin[VIN_CHECK_DIGIT_POSITION] = vin_check_digit(&vin).unwrap();
+            String::from_utf8(vin).unwrap()
+        }
+
+        fn vin(serial: u32) -> String {
+            vin_for_year(serial, 2023)
+        }
+
+        fn vehicle_ids(vehicles: Vec<Vehicle>) -> Vec<String> {
+            vehicles.into_iter().map(|vehicle| vehicle.id).collect()
+        }
+
+        fn sensor_ids(sensors: Vec<Sensor>) -> Vec<String> {
+            sensors.into_iter().map(|sensor| sensor.id).collect()
+        }
+
+        fn sensor_registration(sensor_id: &str, vehicle_id: &str) -> SensorRegistration {
+            SensorRegistration {
+                sensor_id: String::from(sensor_id),
+                did_identifier: format!("did:peaq:sensor:{}", sensor_id.to_lowercase()),
+                vehicle_id: String::from(vehicle_id),
+                sensor_type: SensorType::Gps,
+                manufacturer: String::from("Bosch"),
+                model: String::from("GPS-2023"),
+                accuracy: String::from("±1m"),
+            }
+        }
+
+        /// Xorshift64, so randomized operation sequences are reproducible from a seed.
+        struct XorShift(u64);
+
+        impl XorShift {
+            fn below(&mut self, bound: u64) -> u64 {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                self.0 % bound
+            }
+        }
+
+        /// Stats derived from scratch by walking every vehicle and its sensors.
+        fn expected_stats(registry: &VehicleRegistry) -> RegistryStats {
+            let mut expected = RegistryStats {
+                vehicles_by_status: VehicleStatus::ALL.map(|status| (status, 0)).to_vec(),
+                vehicles_by_engine_type: EngineType::ALL.map(|engine_type| (engine_type, 0)).to_vec(),
+                sensors_by_type: SensorType::ALL.map(|sensor_type| (sensor_type, 0)).to_vec(),
+                sensors_by_status: SensorStatus::LIVE.map(|status| (status, 0)).to_vec(),
+                ..Default::default()
+            };
+            fn bump<K: PartialEq>(counts: &mut [(K, u32)], key: &K) {
+                counts.iter_mut().find(|(candidate, _)| candidate == key).unwrap().1 += 1;
+            }
+
+            for vehicle in registry.list_vehicles(0, MAX_PAGE_SIZE, VehicleFilter::default()).unwrap().vehicles {
+                bump(&mut expected.vehicles_by_status, &vehicle.status);
+                if vehicle.status == VehicleStatus::Deregistered {
+                    continue;
+                }
+                expected.total_vehicles += 1;
+                expected.active_vehicles += u32::from(vehicle.status == VehicleStatus::Active);
+                expected.electric_vehicles += u32::from(vehicle.engine_type == EngineType::Electric);
+                bump(&mut expected.vehicles_by_engine_type, &vehicle.engine_type);
+
+                for sensor in registry.get_vehicle_sensors(vehicle.id, 0, MAX_PAGE_SIZE).unwrap() {
+                    expected.total_sensors += 1;
+                    expected.active_sensors += u32::from(sensor.status == SensorStatus::Active);
+                    bump(&mut expected.sensors_by_type, &sensor.sensor_type);
+                    bump(&mut expected.sensors_by_status, &sensor.status);
+                }
+            }
+            expected
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let registry = VehicleRegistry::new();
+            let stats = registry.get_stats();
+            assert_eq!(stats.total_vehicles, 0);
+            assert_eq!(stats.active_vehicles, 0);
+        }
+
+        #[ink::test]
+        fn register_vehicle_works() {
+            let mut registry = VehicleRegistry::new();
+            
+            let registration = VehicleRegistration {
+                vehicle_id: String::from("AXI_001"),
+                did_identifier: String::from("did:peaq:vehicle:axi_001"),
+                vin: vin(1),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from("AXI001"),
+                engine_type: EngineType::Electric,
+                battery_capacity: Some(75),
+            };
+
+            let result = registry.register_vehicle(registration);
+
+            assert!(result.is_ok());
+            assert!(registry.get_vehicle(String::from("AXI_001")).is_some());
+            
+            let stats = registry.get_stats();
+            assert_eq!(stats.total_vehicles, 1);
+            assert_eq!(stats.active_vehicles, 1);
+            assert_eq!(stats.electric_vehicles, 1);
+        }
+
+        #[ink::test]
+        fn duplicate_vehicle_fails() {
+            let mut registry = VehicleRegistry::new();
+            
+            let registration1 = VehicleRegistration {
+                vehicle_id: String::from("AXI_001"),
+                did_identifier: String::from("did:peaq:vehicle:axi_001"),
+                vin: vin(1),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from("AXI001"),
+                engine_type: EngineType::Electric,
+                battery_capacity: Some(75),
+            };
+            
+            let registration2 = VehicleRegistration {
+                vehicle_id: String::from("AXI_001"),
+                did_identifier: String::from("did:peaq:vehicle:axi_002"),
+                vin: vin(2),
+                make: String::from("BMW"),
+                model: String::from("i3"),
+                year: 2023,
+                license_plate: String::from("AXI002"),
+                engine_type: EngineType::Electric,
+                battery_capacity: Some(42),
+            };
+
+            let result1 = registry.register_vehicle(registration1);
+            assert!(result1.is_ok());
+
+            let result2 = registry.register_vehicle(registration2);
+            assert_eq!(result2, Err(Error::VehicleAlreadyRegistered));
+        }
+
+        #[ink::test]
+        fn register_sensor_works() {
+            let mut registry = VehicleRegistry::new();
+            
+            let vehicle_registration = VehicleRegistration {
+                vehicle_id: String::from("AXI_001"),
+                did_identifier: String::from("did:peaq:vehicle:axi_001"),
+                vin: vin(1),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from("AXI001"),
+                engine_type: EngineType::Electric,
+                battery_capacity: Some(75),
+            };
+
+            registry.register_vehicle(vehicle_registration).unwrap();
+
+            let sensor_registration = SensorRegistration {
+                sensor_id: String::from("GPS_001"),
+                did_identifier: String::from("did:peaq:sensor:gps_001"),
+                vehicle_id: String::from("AXI_001"),
+                sensor_type: SensorType::Gps,
+                manufacturer: String::from("Bosch"),
+                model: String::from("GPS-2023"),
+                accuracy: String::from("±1m"),
+            };
+
+            let result = registry.register_sensor(sensor_registration);
+
+            assert!(result.is_ok());
+            assert!(registry.get_sensor(String::from("GPS_001")).is_some());
+            
+            let sensors = registry.get_vehicle_sensors(String::from("AXI_001"), 0, 10).unwrap();
+            assert_eq!(sensors.len(), 1);
+            assert_eq!(sensors[0].id, "GPS_001");
+        }
+
+        #[ink::test]
+        fn operator_assignment_works() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            
+            let registration = VehicleRegistration {
+                vehicle_id: String::from("AXI_001"),
+                did_identifier: String::from("did:peaq:vehicle:axi_001"),
+                vin: vin(1),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from("AXI001"),
+                engine_type: EngineType::Electric,
+                battery_capacity: Some(75),
+            };
+
+            registry.register_vehicle(registration).unwrap();
+            registry.authorize_operator(accounts.bob).unwrap();
+            
+            let result =
+                registry.assign_operator(String::from("AXI_001"), accounts.bob, OperatorPermissions::all(), None);
+            assert!(result.is_ok());
+
+            let vehicle = registry.get_vehicle(String::from("AXI_001")).unwrap();
+            assert_eq!(vehicle.operator, Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn location_update_works() {
+            let mut registry = VehicleRegistry::new();
+            
+            let registration = VehicleRegistration {
+                vehicle_id: String::from("AXI_001"),
+                did_identifier: String::from("did:peaq:vehicle:axi_001"),
+                vin: vin(1),
+                make: String::from("Tesla"),
+                model: String::from("Model 3"),
+                year: 2023,
+                license_plate: String::from("AXI001"),
+                engine_type: EngineType::Electric,
+                battery_capacity: Some(75),
+            };
+
+            registry.register_vehicle(registration).unwrap();
+
+            let latitude = -1234567; // -1.234567 degrees scaled by 1000000
+            let longitude = 36987654; // 36.987654 degrees scaled by 1000000
+
+            let result = registry.update_location(String::from("AXI_001"), latitude, longitude);
+            assert!(result.is_ok());
+
+            let vehicle = registry.get_vehicle(String::from("AXI_001")).unwrap();
+            assert!(vehicle.location.is_some());
+            
+            let location = vehicle.location.unwrap();
+            assert_eq!(location.latitude, latitude);
+            assert_eq!(location.longitude, longitude);
+        }
+
+        #[ink::test]
+        fn duplicate_vin_fails() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            let result = registry.register_vehicle(registration("AXI_002", &vin(1), "AXI002"));
+            assert_eq!(result, Err(Error::DuplicateVin));
+        }
+
+        #[ink::test]
+        fn duplicate_license_plate_fails() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            let result = registry.register_vehicle(registration("AXI_002", &vin(2), "AXI001"));
+            assert_eq!(result, Err(Error::DuplicateLicensePlate));
+        }
+
+        #[ink::test]
+        fn invalid_vehicle_data_fails() {
+            let mut registry = VehicleRegistry::new();
+
+            let result = registry.register_vehicle(registration("AXI_001", "", "AXI001"));
+            assert_eq!(result, Err(Error::InvalidVehicleData));
+        }
+
+        #[ink::test]
+        fn vin_format_and_check_digit_are_validated() {
+            let mut registry = VehicleRegistry::new();
+            let honda = |vin: &str| VehicleRegistration {
+                make: String::from("Honda"),
+                year: 2003,
+                ..registration("AXI_001", vin, "AXI001")
+            };
+
+            for (vin, error) in [
+                ("1HGCM82633A00435", Error::InvalidVinLength),
+                ("1HGCM82633A0043521", Error::InvalidVinLength),
+                ("1HGCM82633A0O4352", Error::InvalidVinCharacter),
+                ("1hGCM82633A004352", Error::InvalidVinCharacter),
+                ("1HGCM82643A004352", Error::InvalidVinCheckDigit),
+                ("1HGCM8261UA004352", Error::InvalidVinModelYear),
+            ] {
+                assert_eq!(registry.register_vehicle(honda(vin)), Err(error));
+            }
+
+            let wrong_year = VehicleRegistration { year: 2004, ..honda("1HGCM82633A004352") };
+            assert_eq!(registry.register_vehicle(wrong_year), Err(Error::VinYearMismatch));
+
+            registry
+                .set_wmi_manufacturer(String::from("1HG"), Some(String::from("HONDA")))
+                .unwrap();
+            let wrong_make = VehicleRegistration {
+                make: String::from("Toyota"),
+                ..honda("1HGCM82633A004352")
+            };
+            assert_eq!(registry.register_vehicle(wrong_make), Err(Error::VinManufacturerMismatch));
+
+            registry.register_vehicle(honda("1HGCM82633A004352")).unwrap();
+            assert_eq!(registry.get_stats().total_vehicles, 1);
+        }
+
+        #[ink::test]
+        fn vin_check_digit_is_configurable_per_region() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            assert!(registry.is_vin_check_digit_required(VinRegion::NorthAmerica));
+            assert!(!registry.is_vin_check_digit_required(VinRegion::Europe));
+
+            // European VINs commonly leave position 9 unused.
+            registry.register_vehicle(registration("AXI_001", "WBA3E1EA0PF000001", "AXI001")).unwrap();
+
+            registry.set_vin_check_digit_required(VinRegion::Europe, true).unwrap();
+            assert_eq!(
+                registry.register_vehicle(registration("AXI_002", "WBA3E1EA0PF000002", "AXI002")),
+                Err(Error::InvalidVinCheckDigit)
+            );
+            registry.register_vehicle(registration("AXI_002", "WBA3E1EA8PF000001", "AXI002")).unwrap();
+
+            // Nor does a European VIN have to encode the model year unless the region opts in.
+            assert!(registry.is_vin_model_year_required(VinRegion::NorthAmerica));
+            assert!(!registry.is_vin_model_year_required(VinRegion::Europe));
+            let older = |id: &str, vin: &str, plate: &str| VehicleRegistration {
+                year: 2015,
+                ..registration(id, vin, plate)
+            };
+            registry.register_vehicle(older("AXI_003", "WBA3E1EA1PF000003", "AXI003")).unwrap();
+            registry.set_vin_model_year_required(VinRegion::Europe, true).unwrap();
+            assert_eq!(
+                registry.register_vehicle(older("AXI_004", "WBA3E1EA3PF000004", "AXI004")),
+                Err(Error::VinYearMismatch)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                registry.set_vin_check_digit_required(VinRegion::Europe, false),
+                Err(Error::Unauthorized)
+            );
+            assert_eq!(
+                registry.set_vin_model_year_required(VinRegion::Europe, false),
+                Err(Error::Unauthorized)
+            );
+            assert_eq!(
+                registry.set_wmi_manufacturer(String::from("WBA"), Some(String::from("BMW"))),
+                Err(Error::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn missing_vehicle_fails() {
+            let mut registry = VehicleRegistry::new();
+
+            let result = registry.update_location(String::from("AXI_404"), 0, 0);
+            assert_eq!(result, Err(Error::VehicleNotFound));
+        }
+
+        #[ink::test]
+        fn unauthorized_caller_fails() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            let result = registry.update_mileage(String::from("AXI_001"), 100, None);
+            assert_eq!(result, Err(Error::Unauthorized));
+            assert_eq!(registry.authorize_operator(accounts.charlie), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn unapproved_operator_fails() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            let result =
+                registry.assign_operator(String::from("AXI_001"), accounts.bob, OperatorPermissions::all(), None);
+            assert_eq!(result, Err(Error::OperatorNotAuthorized));
+        }
+
+        #[ink::test]
+        fn mileage_rollback_fails() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            registry.update_mileage(String::from("AXI_001"), 1_000, None).unwrap();
+            let result = registry.update_mileage(String::from("AXI_001"), 999, None);
+            assert_eq!(result, Err(Error::MileageRollback));
+        }
+
+        #[ink::test]
+        fn ownership_transfer_works() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.authorize_operator(accounts.django).unwrap();
+            registry
+                .assign_operator(vehicle_id.clone(), accounts.django, OperatorPermissions::all(), None)
+                .unwrap();
+
+            registry.propose_ownership_transfer(vehicle_id.clone(), accounts.bob, false).unwrap();
+            assert!(registry.get_pending_transfer(vehicle_id.clone()).is_some());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                registry.accept_ownership_transfer(vehicle_id.clone()),
+                Err(Error::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.accept_ownership_transfer(vehicle_id.clone()).unwrap();
+
+            let vehicle = registry.get_vehicle(vehicle_id.clone()).unwrap();
+            assert_eq!(vehicle.owner, accounts.bob);
+            assert_eq!(vehicle.operator, None);
+            assert!(registry.get_owner_vehicles(accounts.alice, 0, 10).unwrap().is_empty());
+            assert_eq!(vehicle_ids(registry.get_owner_vehicles(accounts.bob, 0, 10).unwrap()), vec![vehicle_id.clone()]);
+            assert!(registry.get_operator_vehicles(accounts.django, 0, 10).unwrap().is_empty());
+            assert!(registry.get_pending_transfer(vehicle_id.clone()).is_none());
+
+            let history = registry.get_ownership_history(vehicle_id).unwrap();
+            assert_eq!(history.len(), 1);
+            assert_eq!(history[0].owner, accounts.alice);
+        }
+
+        #[ink::test]
+        fn ownership_transfer_can_keep_operator() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.authorize_operator(accounts.django).unwrap();
+            registry
+                .assign_operator(vehicle_id.clone(), accounts.django, OperatorPermissions::all(), None)
+                .unwrap();
+            registry.propose_ownership_transfer(vehicle_id.clone(), accounts.bob, true).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.accept_ownership_transfer(vehicle_id.clone()).unwrap();
+
+            let vehicle = registry.get_vehicle(vehicle_id.clone()).unwrap();
+            assert_eq!(vehicle.operator, Some(accounts.django));
+            assert_eq!(vehicle_ids(registry.get_operator_vehicles(accounts.django, 0, 10).unwrap()), vec![vehicle_id]);
+        }
+
+        #[ink::test]
+        fn cancelled_transfer_cannot_be_accepted() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            assert_eq!(
+                registry.propose_ownership_transfer(vehicle_id.clone(), accounts.alice, false),
+                Err(Error::InvalidTransferTarget)
+            );
+            registry.propose_ownership_transfer(vehicle_id.clone(), accounts.bob, false).unwrap();
+            registry.cancel_transfer(vehicle_id.clone()).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                registry.accept_ownership_transfer(vehicle_id.clone()),
+                Err(Error::NoPendingTransfer)
+            );
+            assert_eq!(registry.get_vehicle(vehicle_id).unwrap().owner, accounts.alice);
+        }
+
+        #[ink::test]
+        fn deregister_vehicle_cleans_up_indexes() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.authorize_operator(accounts.bob).unwrap();
+            registry.assign_operator(vehicle_id.clone(), accounts.bob, OperatorPermissions::all(), None).unwrap();
+            registry.register_sensor(sensor_registration("GPS_001", "AXI_001")).unwrap();
+
+            registry.deregister_vehicle(vehicle_id.clone(), StatusReason::EndOfLife).unwrap();
+
+            let vehicle = registry.get_vehicle(vehicle_id.clone()).unwrap();
+            assert_eq!(vehicle.status, VehicleStatus::Deregistered);
+            assert_eq!(vehicle.operator, None);
+            assert!(registry.get_vehicle_by_vin(vin(1)).is_none());
+            assert!(registry.get_vehicle_by_license(String::from("AXI001")).is_none());
+            assert!(registry.get_owner_vehicles(accounts.alice, 0, 10).unwrap().is_empty());
+            assert!(registry.get_operator_vehicles(accounts.bob, 0, 10).unwrap().is_empty());
+            assert!(registry.get_vehicle_sensors(vehicle_id.clone(), 0, 10).unwrap().is_empty());
+            assert_eq!(registry.get_vehicle_sensor_count(vehicle_id.clone()), 0);
+            assert_eq!(
+                registry.get_sensor(String::from("GPS_001")).unwrap().status,
+                SensorStatus::Retired
+            );
+
+            let stats = registry.get_stats();
+            assert_eq!(stats.total_vehicles, 0);
+            assert_eq!(stats.active_vehicles, 0);
+            assert_eq!(stats.electric_vehicles, 0);
+            assert_eq!(stats.total_sensors, 0);
+            assert_eq!(stats.active_sensors, 0);
+
+            assert_eq!(
+                registry.deregister_vehicle(vehicle_id.clone(), StatusReason::EndOfLife),
+                Err(Error::VehicleDeregistered)
+            );
+            assert_eq!(registry.update_mileage(vehicle_id, 10, None), Err(Error::VehicleDeregistered));
+        }
+
+        #[ink::test]
+        fn deregistered_vin_only_reusable_by_same_vehicle() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.deregister_vehicle(String::from("AXI_001"), StatusReason::EndOfLife).unwrap();
+
+            assert_eq!(
+                registry.register_vehicle(registration("AXI_002", &vin(1), "AXI002")),
+                Err(Error::RetiredVin)
+            );
+
+            let mut other_make = registration("AXI_001", &vin(1), "AXI002");
+            other_make.make = String::from("BMW");
+            assert_eq!(registry.register_vehicle(other_make), Err(Error::RetiredVin));
+
+            assert_eq!(
+                registry.register_vehicle(registration("AXI_001", &vin(2), "AXI002")),
+                Err(Error::VehicleAlreadyRegistered)
+            );
+
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI002")).unwrap();
+            assert!(registry.is_vehicle_active(String::from("AXI_001")));
+            assert_eq!(registry.get_stats().total_vehicles, 1);
+            assert_eq!(registry.get_ownership_history(String::from("AXI_001")).unwrap().len(), 1);
+        }
+
+        #[ink::test]
+        fn reregistration_keeps_owner_and_mileage() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.update_mileage(vehicle_id.clone(), 80_000, None).unwrap();
+            registry.deregister_vehicle(vehicle_id.clone(), StatusReason::EndOfLife).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")),
+                Err(Error::StatusTransitionNotPermitted)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            let vehicle = registry.get_vehicle(vehicle_id.clone()).unwrap();
+            assert_eq!(vehicle.owner, accounts.bob);
+            assert_eq!(vehicle.mileage, 80_000);
+            assert_eq!(registry.get_owner_vehicle_count(accounts.bob), 1);
+            assert_eq!(registry.get_owner_vehicle_count(accounts.alice), 0);
+
+            let history = registry.get_status_history(vehicle_id.clone(), 0, 10).unwrap();
+            let last = history.last().unwrap();
+            assert_eq!(last.from, VehicleStatus::Deregistered);
+            assert_eq!(last.to, VehicleStatus::Active);
+            assert_eq!(last.reason, StatusReason::Reregistered);
+            assert_eq!(last.changed_by, accounts.alice);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.update_mileage(vehicle_id, 100, None), Err(Error::MileageRollback));
+        }
+
+        #[ink::test]
+        fn status_update_cannot_deregister() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            let result = registry.update_vehicle_status(
+                String::from("AXI_001"),
+                VehicleStatus::Deregistered,
+                StatusReason::EndOfLife,
+            );
+            assert_eq!(result, Err(Error::InvalidStatusTransition));
+            assert!(registry.is_vehicle_active(String::from("AXI_001")));
+        }
+
+        #[ink::test]
+        fn license_plate_change_works() {
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_vehicle(registration("AXI_002", &vin(2), "AXI002")).unwrap();
+
+            assert_eq!(
+                registry.update_license_plate(vehicle_id.clone(), String::from("AXI002")),
+                Err(Error::DuplicateLicensePlate)
+            );
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            let changed_at = ink::env::block_timestamp::<ink::env::DefaultEnvironment>();
+            registry.update_license_plate(vehicle_id.clone(), String::from("KCA 123X")).unwrap();
+
+            assert!(registry.get_vehicle_by_license(String::from("AXI001")).is_none());
+            assert_eq!(
+                registry.get_vehicle_by_license(String::from("KCA 123X")).unwrap().id,
+                vehicle_id
+            );
+
+            let history = registry.get_plate_history(vehicle_id.clone()).unwrap();
+            assert_eq!(history.len(), 2);
+            assert_eq!(history[0].plate, "AXI001");
+            assert_eq!(history[0].released_at, Some(changed_at));
+            assert_eq!(history[1].plate, "KCA 123X");
+            assert_eq!(history[1].released_at, None);
+
+            // The freed plate can be picked up by another vehicle.
+            registry.update_license_plate(String::from("AXI_002"), String::from("AXI001")).unwrap();
+        }
+
+        #[ink::test]
+        fn plate_holder_lookup_by_time_works() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_vehicle(registration("AXI_002", &vin(2), "AXI002")).unwrap();
+            let registered_at = ink::env::block_timestamp::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            registry.update_license_plate(String::from("AXI_001"), String::from("AXI003")).unwrap();
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            let reassigned_at = ink::env::block_timestamp::<ink::env::DefaultEnvironment>();
+            registry.update_license_plate(String::from("AXI_002"), String::from("AXI001")).unwrap();
+
+            let plate = String::from("AXI001");
+            assert_eq!(registry.get_plate_holder_at(plate.clone(), registered_at), Some(String::from("AXI_001")));
+            assert_eq!(registry.get_plate_holder_at(plate.clone(), reassigned_at - 1), None);
+            assert_eq!(registry.get_plate_holder_at(plate, reassigned_at), Some(String::from("AXI_002")));
+        }
+
+        #[ink::test]
+        fn sensor_status_and_calibration_work() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let sensor_id = String::from("GPS_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_sensor(sensor_registration("GPS_001", "AXI_001")).unwrap();
+
+            registry.update_sensor_status(sensor_id.clone(), SensorStatus::Calibrating).unwrap();
+            assert_eq!(registry.get_stats().active_sensors, 0);
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            registry.record_calibration(sensor_id.clone(), [7u8; 32], accounts.eve).unwrap();
+
+            let sensor = registry.get_sensor(sensor_id.clone()).unwrap();
+            assert_eq!(sensor.status, SensorStatus::Active);
+            assert_eq!(sensor.last_calibrated, ink::env::block_timestamp::<ink::env::DefaultEnvironment>());
+            assert_eq!(registry.get_stats().active_sensors, 1);
+
+            let calibrations = registry.get_calibration_history(sensor_id.clone()).unwrap();
+            assert_eq!(calibrations.len(), 1);
+            assert_eq!(calibrations[0].certificate_hash, [7u8; 32]);
+            assert_eq!(calibrations[0].technician, accounts.eve);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                registry.update_sensor_status(sensor_id, SensorStatus::Faulty),
+                Err(Error::Unauthorized)
+            );
+            assert_eq!(
+                registry.record_calibration(String::from("GPS_404"), [0u8; 32], accounts.eve),
+                Err(Error::SensorNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn remove_sensor_works() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_sensor(sensor_registration("GPS_001", "AXI_001")).unwrap();
+            registry.register_sensor(sensor_registration("GPS_002", "AXI_001")).unwrap();
+            registry.update_sensor_status(String::from("GPS_002"), SensorStatus::Faulty).unwrap();
+
+            registry.remove_sensor(String::from("GPS_002")).unwrap();
+
+            assert_eq!(
+                registry.get_sensor(String::from("GPS_002")).unwrap().status,
+                SensorStatus::Retired
+            );
+            assert_eq!(
+                registry.update_sensor_status(String::from("GPS_002"), SensorStatus::Active),
+                Err(Error::SensorRetired)
+            );
+            assert_eq!(
+                registry.update_sensor_status(String::from("GPS_001"), SensorStatus::Retired),
+                Err(Error::InvalidStatusTransition)
+            );
+            assert_eq!(
+                sensor_ids(registry.get_vehicle_sensors(String::from("AXI_001"), 0, 10).unwrap()),
+                vec![String::from("GPS_001")]
+            );
+            let stats = registry.get_stats();
+            assert_eq!(stats.total_sensors, 1);
+            assert_eq!(stats.active_sensors, 1);
+        }
+
+        #[ink::test]
+        fn sensors_per_vehicle_are_capped() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_vehicle(registration("AXI_002", &vin(2), "AXI002")).unwrap();
+            for i in 0..MAX_SENSORS_PER_VEHICLE - 1 {
+                registry.register_sensor(sensor_registration(&format!("GPS_{i}"), "AXI_001")).unwrap();
+            }
+
+            let batch = vec![
+                sensor_registration("GPS_LAST", "AXI_001"),
+                sensor_registration("GPS_OVER", "AXI_001"),
+            ];
+            assert_eq!(
+                registry.register_sensors(batch, BatchMode::Atomic),
+                Ok(vec![Err(Error::BatchAborted), Err(Error::SensorLimitReached)])
+            );
+
+            registry.register_sensor(sensor_registration("GPS_LAST", "AXI_001")).unwrap();
+            assert_eq!(
+                registry.register_sensor(sensor_registration("GPS_OVER", "AXI_001")),
+                Err(Error::SensorLimitReached)
+            );
+            registry.register_sensor(sensor_registration("GPS_OVER", "AXI_002")).unwrap();
+            assert_eq!(
+                registry.reassign_sensor(String::from("GPS_OVER"), String::from("AXI_001")),
+                Err(Error::SensorLimitReached)
+            );
+
+            // Retiring the vehicle reaches every one of its sensors.
+            registry.deregister_vehicle(String::from("AXI_001"), StatusReason::EndOfLife).unwrap();
+            assert_eq!(registry.get_stats().total_sensors, 1);
+            assert_eq!(
+                registry.get_sensor(String::from("GPS_LAST")).unwrap().status,
+                SensorStatus::Retired
+            );
+        }
+
+        #[ink::test]
+        fn retired_sensor_id_cannot_be_reused() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let sensor_id = String::from("GPS_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_sensor(sensor_registration("GPS_001", "AXI_001")).unwrap();
+            registry.record_calibration(sensor_id.clone(), [1u8; 32], accounts.bob).unwrap();
+            registry.set_sensor_key(sensor_id.clone(), [7u8; 32]).unwrap();
+
+            registry.remove_sensor(sensor_id.clone()).unwrap();
+
+            assert_eq!(
+                registry.register_sensor(sensor_registration("GPS_001", "AXI_001")),
+                Err(Error::SensorAlreadyRegistered)
+            );
+            assert_eq!(registry.get_sensor_key(sensor_id.clone()), None);
+            assert_eq!(registry.get_calibration_history(sensor_id.clone()).unwrap().len(), 1);
+            assert!(registry.get_installation_history(sensor_id).unwrap()[0].removed_at.is_some());
+        }
+
+        #[ink::test]
+        fn reassign_sensor_works() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let sensor_id = String::from("GPS_001");
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_sensor(sensor_registration("GPS_001", "AXI_001")).unwrap();
+            registry.register_vehicle(registration("AXI_003", &vin(3), "AXI003")).unwrap();
+            let installed_at = ink::env::block_timestamp::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.register_vehicle(registration("AXI_002", &vin(2), "AXI002")).unwrap();
+
+            // Each owner only has authority over one side of the move.
+            assert_eq!(
+                registry.reassign_sensor(sensor_id.clone(), String::from("AXI_002")),
+                Err(Error::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                registry.reassign_sensor(sensor_id.clone(), String::from("AXI_002")),
+                Err(Error::Unauthorized)
+            );
+            assert_eq!(
+                registry.reassign_sensor(sensor_id.clone(), String::from("AXI_001")),
+                Err(Error::SensorAlreadyInstalled)
+            );
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            let moved_at = ink::env::block_timestamp::<ink::env::DefaultEnvironment>();
+            registry.reassign_sensor(sensor_id.clone(), String::from("AXI_003")).unwrap();
+
+            assert!(registry.get_vehicle_sensors(String::from("AXI_001"), 0, 10).unwrap().is_empty());
+            assert_eq!(
+                sensor_ids(registry.get_vehicle_sensors(String::from("AXI_003"), 0, 10).unwrap()),
+                vec![sensor_id.clone()]
+            );
+            assert_eq!(registry.get_sensor(sensor_id.clone()).unwrap().vehicle_id, "AXI_003");
+
+            let history = registry.get_installation_history(sensor_id).unwrap();
+            assert_eq!(
+                history,
+                vec![
+                    InstallationRecord {
+                        vehicle_id: String::from("AXI_001"),
+                        installed_at,
+                        removed_at: Some(moved_at),
+                    },
+                    InstallationRecord {
+                        vehicle_id: String::from("AXI_003"),
+                        installed_at: moved_at,
+                        removed_at: None,
+                    },
+                ]
+            );
+        }
+
+        #[ink::test]
+        fn location_history_is_bounded_ring_buffer() {
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.set_location_history_capacity(3).unwrap();
+
+            let mut timestamps = Vec::new();
+            for i in 0..5 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+                timestamps.push(ink::env::block_timestamp::<ink::env::DefaultEnvironment>());
+                registry.update_location(vehicle_id.clone(), i, i).unwrap();
+            }
+
+            let history = registry.get_location_history(vehicle_id.clone(), 0, u64::MAX, 10).unwrap();
+            let latitudes: Vec<i32> = history.iter().map(|location| location.latitude).collect();
+            assert_eq!(latitudes, vec![2, 3, 4]);
+
+            let window = registry.get_location_history(vehicle_id.clone(), timestamps[2], timestamps[3], 10).unwrap();
+            assert_eq!(window.len(), 2);
+            assert_eq!(window[0].timestamp, timestamps[2]);
+
+            let latest = registry.get_location_history(vehicle_id, 0, u64::MAX, 1).unwrap();
+            assert_eq!(latest[0].latitude, 4);
+        }
+
+        #[ink::test]
+        fn location_history_survives_capacity_change() {
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.set_location_history_capacity(4).unwrap();
+
+            for i in 0..6 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+                registry.update_location(vehicle_id.clone(), i, i).unwrap();
+            }
+
+            registry.set_location_history_capacity(2).unwrap();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            registry.update_location(vehicle_id.clone(), 6, 6).unwrap();
+
+            let history = registry.get_location_history(vehicle_id, 0, u64::MAX, 10).unwrap();
+            let latitudes: Vec<i32> = history.iter().map(|location| location.latitude).collect();
+            assert_eq!(latitudes, vec![5, 6]);
+
+            assert_eq!(
+                registry.set_location_history_capacity(MAX_LOCATION_HISTORY_CAPACITY + 1),
+                Err(Error::InvalidConfiguration)
+            );
+        }
+
+        #[ink::test]
+        fn geofence_containment_works() {
+            // ~1km radius around Nairobi CBD
+            let circle = GeofenceShape::Circle {
+                latitude: -1_286_389,
+                longitude: 36_817_223,
+                radius_m: 1_000,
+            };
+            assert!(circle.contains(-1_286_389, 36_817_223));
+            assert!(circle.contains(-1_280_000, 36_817_223)); // ~710m north
+            assert!(!circle.contains(-1_270_000, 36_817_223)); // ~1.8km north
+
+            let square = GeofenceShape::Polygon {
+                vertices: vec![(0, 0), (0, 1_000_000), (1_000_000, 1_000_000), (1_000_000, 0)],
+            };
+            assert!(square.contains(500_000, 500_000));
+            assert!(!square.contains(1_500_000, 500_000));
+            assert!(!square.contains(500_000, -1));
+
+            assert!(!GeofenceShape::Polygon { vertices: vec![(0, 0), (1, 1)] }.is_valid());
+            assert!(!GeofenceShape::Circle { latitude: 91_000_000, longitude: 0, radius_m: 10 }.is_valid());
+        }
+
+        #[ink::test]
+        fn geofence_events_and_suspension_work() {
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            let depot = registry
+                .create_geofence(
+                    GeofenceShape::Polygon {
+                        vertices: vec![(0, 0), (0, 1_000_000), (1_000_000, 1_000_000), (1_000_000, 0)],
+                    },
+                    GeofenceKind::Inclusion,
+                    true,
+                )
+                .unwrap();
+            registry.attach_geofence(vehicle_id.clone(), depot).unwrap();
+            assert_eq!(
+                registry.attach_geofence(vehicle_id.clone(), depot),
+                Err(Error::GeofenceAlreadyAttached)
+            );
+            assert_eq!(
+                registry.create_geofence(
+                    GeofenceShape::Circle { latitude: 0, longitude: 0, radius_m: 0 },
+                    GeofenceKind::Exclusion,
+                    false,
+                ),
+                Err(Error::InvalidGeofence)
+            );
+
+            registry.update_location(vehicle_id.clone(), 990_000, 500_000).unwrap();
+            assert!(registry.is_vehicle_active(vehicle_id.clone()));
+
+            let events_before = ink::env::test::recorded_events().count();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(600_000);
+            registry.update_location(vehicle_id.clone(), 1_010_000, 500_000).unwrap();
+
+            // LocationUpdated, GeofenceExited, GeofenceViolation, VehicleStatusChanged
+            assert_eq!(ink::env::test::recorded_events().count() - events_before, 4);
+            let vehicle = registry.get_vehicle(vehicle_id.clone()).unwrap();
+            assert_eq!(vehicle.status, VehicleStatus::Suspended);
+            assert_eq!(registry.get_stats().active_vehicles, 0);
+
+            registry.detach_geofence(vehicle_id.clone(), depot).unwrap();
+            assert!(registry.get_vehicle_geofences(vehicle_id).is_empty());
+        }
+
+        #[ink::test]
+        fn invalid_coordinates_fail() {
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            assert_eq!(
+                registry.update_location(vehicle_id.clone(), 90_000_001, 0),
+                Err(Error::InvalidCoordinates)
+            );
+            assert_eq!(
+                registry.update_location(vehicle_id.clone(), 0, -180_000_001),
+                Err(Error::InvalidCoordinates)
+            );
+            assert!(registry.get_vehicle(vehicle_id).unwrap().location.is_none());
+        }
+
+        #[ink::test]
+        fn repeated_fix_in_same_block_fails() {
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            registry.update_location(vehicle_id.clone(), -1_234_567, 36_987_654).unwrap();
+            assert_eq!(
+                registry.update_location(vehicle_id, -1_234_567, 36_987_654),
+                Err(Error::StaleLocation)
+            );
+        }
+
+        #[ink::test]
+        fn implausible_jump_fails() {
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.update_location(vehicle_id.clone(), -1_286_389, 36_817_223).unwrap();
+
+            // Nairobi to Mombasa (~440km) in one minute.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(60_000);
+            assert_eq!(
+                registry.update_location(vehicle_id.clone(), -4_043_477, 39_668_206),
+                Err(Error::ImplausibleLocationJump)
+            );
+
+            // ~9km in ten minutes is fine.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(600_000);
+            registry.update_location(vehicle_id.clone(), -1_366_389, 36_817_223).unwrap();
+
+            registry.set_max_speed(0).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(660_000);
+            registry.update_location(vehicle_id, -4_043_477, 39_668_206).unwrap();
+        }
+
+        fn odometer_keypair() -> schnorrkel::Keypair {
+            schnorrkel::MiniSecretKey::from_bytes(&[42u8; 32])
+                .unwrap()
+                .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519)
+        }
+
+        fn sign_mileage(
+            keypair: &schnorrkel::Keypair,
+            vehicle_id: &str,
+            nonce: u32,
+            previous_mileage: u32,
+            mileage: u32,
+        ) -> [u8; 64] {
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            let message = mileage_attestation_message(contract, vehicle_id, nonce, previous_mileage, mileage);
+            keypair.sign_simple(b"substrate", &message).to_bytes()
+        }
+
+        #[ink::test]
+        fn mileage_history_is_recorded() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.authorize_operator(accounts.bob).unwrap();
+            registry.assign_operator(vehicle_id.clone(), accounts.bob, OperatorPermissions::all(), None).unwrap();
+
+            registry.update_mileage(vehicle_id.clone(), 1_200, None).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.update_mileage(vehicle_id.clone(), 1_950, None).unwrap();
+
+            assert_eq!(registry.get_mileage_record_count(vehicle_id.clone()), 2);
+            let history = registry.get_mileage_history(vehicle_id.clone(), 0, 10).unwrap();
+            assert_eq!(history.len(), 2);
+            assert_eq!(history[0].mileage, 1_200);
+            assert_eq!(history[0].reported_by, accounts.alice);
+            assert_eq!(history[1].mileage, 1_950);
+            assert_eq!(history[1].reported_by, accounts.bob);
+            assert_eq!(registry.get_mileage_history(vehicle_id.clone(), 1, 10).unwrap().len(), 1);
+
+            for step in 1..=MAX_PAGE_SIZE {
+                registry.update_mileage(vehicle_id.clone(), 1_950 + step, None).unwrap();
+            }
+            let page = registry.get_mileage_history(vehicle_id, 0, u32::MAX).unwrap();
+            assert_eq!(page.len(), MAX_PAGE_SIZE as usize);
+        }
+
+        #[ink::test]
+        fn attested_mileage_is_verified() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            let keypair = odometer_keypair();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_sensor(SensorRegistration {
+                sensor_type: SensorType::Odometer,
+                ..sensor_registration("ODO_001", "AXI_001")
+            }).unwrap();
+            registry.register_sensor(sensor_registration("GPS_001", "AXI_001")).unwrap();
+
+            let attestation = MileageAttestation {
+                sensor_id: String::from("ODO_001"),
+                signature: sign_mileage(&keypair, "AXI_001", 0, 0, 5_000),
+            };
+            assert_eq!(
+                registry.update_mileage(vehicle_id.clone(), 5_000, Some(attestation.clone())),
+                Err(Error::SensorKeyMissing)
+            );
+
+            // The owner cannot vouch for its own sensor's key.
+            assert_eq!(
+                registry.set_sensor_key(String::from("ODO_001"), keypair.public.to_bytes()),
+                Err(Error::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            registry.set_sensor_key(String::from("ODO_001"), keypair.public.to_bytes()).unwrap();
+            registry.set_sensor_key(String::from("GPS_001"), keypair.public.to_bytes()).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+
+            // Signature is bound to the reading it was made for.
+            assert_eq!(
+                registry.update_mileage(vehicle_id.clone(), 5_001, Some(attestation.clone())),
+                Err(Error::InvalidAttestation)
+            );
+            // Only odometer sensors can attest.
+            let gps_attestation = MileageAttestation {
+                sensor_id: String::from("GPS_001"),
+                ..attestation.clone()
+            };
+            assert_eq!(
+                registry.update_mileage(vehicle_id.clone(), 5_000, Some(gps_attestation)),
+                Err(Error::InvalidAttestation)
+            );
+
+            registry.update_mileage(vehicle_id.clone(), 5_000, Some(attestation.clone())).unwrap();
+            let history = registry.get_mileage_history(vehicle_id.clone(), 0, 1).unwrap();
+            assert_eq!(history[0].attestation, Some(attestation.clone()));
+
+            // A used signature cannot be replayed.
+            assert_eq!(
+                registry.update_mileage(vehicle_id.clone(), 5_000, Some(attestation)),
+                Err(Error::InvalidAttestation)
+            );
+            let attestation = MileageAttestation {
+                sensor_id: String::from("ODO_001"),
+                signature: sign_mileage(&keypair, "AXI_001", 1, 5_000, 5_400),
+            };
+            registry.update_mileage(vehicle_id, 5_400, Some(attestation)).unwrap();
+        }
+
+        #[ink::test]
+        fn service_visit_enters_and_leaves_maintenance() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.update_mileage(vehicle_id.clone(), 10_000, None).unwrap();
+            registry.grant_role(Role::ServiceProvider, accounts.eve).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.begin_service(vehicle_id.clone()), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                registry.record_service(vehicle_id.clone(), ServiceType::OilChange, 10_000, [1u8; 32], 50),
+                Err(Error::NoServiceInProgress)
+            );
+            registry.begin_service(vehicle_id.clone()).unwrap();
+            assert_eq!(registry.begin_service(vehicle_id.clone()), Err(Error::ServiceInProgress));
+            assert_eq!(registry.get_vehicle(vehicle_id.clone()).unwrap().status, VehicleStatus::Maintenance);
+            assert_eq!(registry.get_stats().active_vehicles, 0);
+
+            assert_eq!(
+                registry.record_service(vehicle_id.clone(), ServiceType::OilChange, 9_000, [1u8; 32], 50),
+                Err(Error::MileageRollback)
+            );
+            registry.record_service(vehicle_id.clone(), ServiceType::OilChange, 10_000, [1u8; 32], 50).unwrap();
+            registry.record_service(vehicle_id.clone(), ServiceType::Tires, 10_000, [2u8; 32], 400).unwrap();
+            registry.complete_service(vehicle_id.clone()).unwrap();
+
+            assert_eq!(registry.get_vehicle(vehicle_id.clone()).unwrap().status, VehicleStatus::Active);
+            assert_eq!(registry.get_stats().active_vehicles, 1);
+            assert!(registry.get_service_visit(vehicle_id.clone()).is_none());
+
+            let history = registry.get_service_history(vehicle_id.clone(), 0, 10).unwrap();
+            assert_eq!(history.len(), 2);
+            assert_eq!(history[0].service_type, ServiceType::OilChange);
+            assert_eq!(history[1].cost, 400);
+            assert_eq!(history[1].provider, accounts.eve);
+
+            registry.begin_service(vehicle_id.clone()).unwrap();
+            for _ in 0..MAX_PAGE_SIZE {
+                registry.record_service(vehicle_id.clone(), ServiceType::Inspection, 10_000, [3u8; 32], 0).unwrap();
+            }
+            let page = registry.get_service_history(vehicle_id, 0, u32::MAX).unwrap();
+            assert_eq!(page.len(), MAX_PAGE_SIZE as usize);
+        }
+
+        #[ink::test]
+        fn service_visits_require_the_provider_role() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.grant_role(Role::ServiceProvider, accounts.eve).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            assert_eq!(registry.begin_service(vehicle_id.clone()), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            registry.begin_service(vehicle_id.clone()).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            assert_eq!(registry.complete_service(vehicle_id), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn service_due_reminders_work() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.grant_role(Role::ServiceProvider, accounts.eve).unwrap();
+            registry.set_service_interval(vehicle_id.clone(), ServiceType::OilChange, Some(5_000), None).unwrap();
+            registry.set_service_interval(vehicle_id.clone(), ServiceType::Inspection, None, Some(1_000)).unwrap();
+
+            registry.update_mileage(vehicle_id.clone(), 6_000, None).unwrap();
+            let due = registry.get_service_due(vehicle_id.clone()).unwrap();
+            assert_eq!(due.len(), 2);
+            assert_eq!(due[0].due_mileage, Some(5_000));
+            assert!(due[0].overdue);
+            assert!(!due[1].overdue);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            registry.begin_service(vehicle_id.clone()).unwrap();
+            registry.record_service(vehicle_id.clone(), ServiceType::OilChange, 6_000, [1u8; 32], 50).unwrap();
+            registry.complete_service(vehicle_id.clone()).unwrap();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
+            let due = registry.get_service_due(vehicle_id).unwrap();
+            assert_eq!(due[0].due_mileage, Some(11_000));
+            assert!(!due[0].overdue);
+            assert_eq!(due[1].due_at, Some(1_000));
+            assert!(due[1].overdue);
+        }
+
+        #[ink::test]
+        fn owner_vehicles_are_paginated() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            for i in 0..5 {
+                let id = format!("AXI_00{i}");
+                registry.register_vehicle(registration(&id, &vin(i), &format!("PLATE{i}"))).unwrap();
+            }
+
+            assert_eq!(registry.get_owner_vehicle_count(accounts.alice), 5);
+            assert_eq!(
+                vehicle_ids(registry.get_owner_vehicles(accounts.alice, 0, 2).unwrap()),
+                vec![String::from("AXI_000"), String::from("AXI_001")]
+            );
+            assert_eq!(registry.get_owner_vehicles(accounts.alice, 4, 10).unwrap().len(), 1);
+            assert!(registry.get_owner_vehicles(accounts.alice, 5, 10).unwrap().is_empty());
+
+            // Removing from the middle moves the last entry into the gap.
+            registry.deregister_vehicle(String::from("AXI_001"), StatusReason::EndOfLife).unwrap();
+            assert_eq!(registry.get_owner_vehicle_count(accounts.alice), 4);
+            assert_eq!(
+                vehicle_ids(registry.get_owner_vehicles(accounts.alice, 0, 10).unwrap()),
+                vec![
+                    String::from("AXI_000"),
+                    String::from("AXI_004"),
+                    String::from("AXI_002"),
+                    String::from("AXI_003"),
+                ]
+            );
+        }
+
+        #[ink::test]
+        fn vehicle_sensors_are_paginated() {
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            for i in 0..4 {
+                registry.register_sensor(sensor_registration(&format!("GPS_00{i}"), "AXI_001")).unwrap();
+            }
+
+            registry.remove_sensor(String::from("GPS_000")).unwrap();
+            assert_eq!(registry.get_vehicle_sensor_count(vehicle_id.clone()), 3);
+            assert_eq!(
+                sensor_ids(registry.get_vehicle_sensors(vehicle_id.clone(), 1, 2).unwrap()),
+                vec![String::from("GPS_001"), String::from("GPS_002")]
+            );
+            assert_eq!(registry.get_vehicle_sensors(vehicle_id, 0, u32::MAX).unwrap().len(), 3);
+        }
+
+        #[ink::test]
+        fn list_vehicles_iterates_with_cursor_and_filters() {
+            let mut registry = VehicleRegistry::new();
+            for i in 0..6 {
+                let mut vehicle = registration(&format!("AXI_00{i}"), &vin_for_year(i, 2020 + i as u16), &format!("PLATE{i}"));
+                vehicle.year = 2020 + i as u16;
+                if i % 2 == 1 {
+                    vehicle.make = String::from("Toyota");
+                    vehicle.model = String::from("Prius");
+                    vehicle.engine_type = EngineType::Hybrid;
+                }
+                registry.register_vehicle(vehicle).unwrap();
+            }
+            registry
+                .update_vehicle_status(String::from("AXI_002"), VehicleStatus::Maintenance, StatusReason::Manual)
+                .unwrap();
+            registry.deregister_vehicle(String::from("AXI_004"), StatusReason::EndOfLife).unwrap();
+
+            let first = registry.list_vehicles(0, 4, VehicleFilter::default()).unwrap();
+            assert_eq!(first.vehicles.len(), 4);
+            assert_eq!(first.next_cursor, Some(4));
+            let rest = registry.list_vehicles(4, 4, VehicleFilter::default()).unwrap();
+            assert_eq!(vehicle_ids(rest.vehicles), vec![String::from("AXI_004"), String::from("AXI_005")]);
+            assert_eq!(rest.next_cursor, None);
+
+            let hybrids = VehicleFilter {
+                engine_type: Some(EngineType::Hybrid),
+                make: Some(String::from("Toyota")),
+                min_year: Some(2022),
+                ..Default::default()
+            };
+            assert_eq!(
+                vehicle_ids(registry.list_vehicles(0, 10, hybrids).unwrap().vehicles),
+                vec![String::from("AXI_003"), String::from("AXI_005")]
+            );
+
+            let active_teslas = VehicleFilter {
+                status: Some(VehicleStatus::Active),
+                model: Some(String::from("Model 3")),
+                max_year: Some(2024),
+                ..Default::default()
+            };
+            assert_eq!(
+                vehicle_ids(registry.list_vehicles(0, 10, active_teslas).unwrap().vehicles),
+                vec![String::from("AXI_000")]
+            );
+
+            // Re-registering a deregistered vehicle does not index it twice.
+            let mut again = registration("AXI_004", &vin_for_year(4, 2024), "PLATE4");
+            again.year = 2024;
+            registry.register_vehicle(again).unwrap();
+            assert_eq!(registry.get_vehicle_id_count(), 6);
+        }
+
+        #[ink::test]
+        fn operator_permissions_are_scoped() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.authorize_operator(accounts.bob).unwrap();
+            let driver = OperatorPermissions {
+                can_update_location: true,
+                can_update_mileage: true,
+                ..Default::default()
+            };
+            registry.assign_operator(vehicle_id.clone(), accounts.bob, driver, None).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.update_location(vehicle_id.clone(), 52_520_000, 13_405_000).unwrap();
+            registry.update_mileage(vehicle_id.clone(), 120, None).unwrap();
+            assert_eq!(
+                registry.update_vehicle_status(vehicle_id.clone(), VehicleStatus::Maintenance, StatusReason::Manual),
+                Err(Error::Unauthorized)
+            );
+            assert_eq!(
+                registry.register_sensor(sensor_registration("GPS_001", "AXI_001")),
+                Err(Error::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn operator_assignment_lapses_after_expiry() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.authorize_operator(accounts.bob).unwrap();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(
+                registry.assign_operator(vehicle_id.clone(), accounts.bob, OperatorPermissions::all(), Some(1_000)),
+                Err(Error::InvalidOperatorExpiry)
+            );
+            registry
+                .assign_operator(vehicle_id.clone(), accounts.bob, OperatorPermissions::all(), Some(5_000))
+                .unwrap();
+            assert_eq!(registry.expire_operator(vehicle_id.clone()), Err(Error::OperatorNotExpired));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.update_mileage(vehicle_id.clone(), 50, None).unwrap();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            assert_eq!(registry.update_mileage(vehicle_id.clone(), 80, None), Err(Error::OperatorExpired));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            let events_before = ink::env::test::recorded_events().count();
+            registry.expire_operator(vehicle_id.clone()).unwrap();
+            // OperatorExpired
+            assert_eq!(ink::env::test::recorded_events().count() - events_before, 1);
+
+            let vehicle = registry.get_vehicle(vehicle_id.clone()).unwrap();
+            assert_eq!(vehicle.operator, None);
+            assert_eq!(registry.get_operator_assignment(vehicle_id.clone()), None);
+            assert!(registry.get_operator_vehicles(accounts.bob, 0, 10).unwrap().is_empty());
+            assert_eq!(registry.expire_operator(vehicle_id), Err(Error::NoOperatorAssigned));
+        }
+
+        #[ink::test]
+        fn operator_can_be_unassigned_or_resign() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.authorize_operator(accounts.bob).unwrap();
+            registry
+                .assign_operator(vehicle_id.clone(), accounts.bob, OperatorPermissions::all(), None)
+                .unwrap();
+
+            registry.unassign_operator(vehicle_id.clone()).unwrap();
+            assert_eq!(registry.get_vehicle(vehicle_id.clone()).unwrap().operator, None);
+            assert_eq!(registry.get_operator_assignment(vehicle_id.clone()), None);
+            assert_eq!(registry.unassign_operator(vehicle_id.clone()), Err(Error::NoOperatorAssigned));
+
+            registry
+                .assign_operator(vehicle_id.clone(), accounts.bob, OperatorPermissions::all(), None)
+                .unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.resign_operator(vehicle_id.clone()), Err(Error::Unauthorized));
+            assert_eq!(registry.unassign_operator(vehicle_id.clone()), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.resign_operator(vehicle_id.clone()).unwrap();
+            assert_eq!(registry.get_vehicle(vehicle_id).unwrap().operator, None);
+            assert_eq!(registry.get_operator_vehicle_count(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn deauthorized_operator_loses_access_and_is_released_in_pages() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            registry.authorize_operator(accounts.bob).unwrap();
+            for i in 0..3 {
+                let id = format!("AXI_00{i}");
+                registry.register_vehicle(registration(&id, &vin(i), &format!("PLATE{i}"))).unwrap();
+                registry.assign_operator(id, accounts.bob, OperatorPermissions::all(), None).unwrap();
+            }
+
+            assert_eq!(registry.deauthorize_operator(accounts.bob, false), Ok(3));
+            assert!(!registry.is_operator_authorized(accounts.bob));
+            assert_eq!(registry.get_operator_vehicle_count(accounts.bob), 3);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                registry.update_mileage(String::from("AXI_000"), 100, None),
+                Err(Error::OperatorNotAuthorized)
+            );
+            assert_eq!(registry.release_operator_vehicles(accounts.bob, 10), Err(Error::Unauthorized));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+
+            let events_before = ink::env::test::recorded_events().count();
+            assert_eq!(registry.release_operator_vehicles(accounts.bob, 2), Ok(1));
+            // One OperatorUnassigned per vehicle
+            assert_eq!(ink::env::test::recorded_events().count() - events_before, 2);
+            assert_eq!(registry.release_operator_vehicles(accounts.bob, 2), Ok(0));
+            assert_eq!(registry.get_operator_vehicle_count(accounts.bob), 0);
+            for i in 0..3 {
+                assert_eq!(registry.get_vehicle(format!("AXI_00{i}")).unwrap().operator, None);
+            }
+        }
+
+        #[ink::test]
+        fn deauthorize_operator_can_cascade() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            registry.authorize_operator(accounts.bob).unwrap();
+            for i in 0..2 {
+                let id = format!("AXI_00{i}");
+                registry.register_vehicle(registration(&id, &vin(i), &format!("PLATE{i}"))).unwrap();
+                registry.assign_operator(id, accounts.bob, OperatorPermissions::all(), None).unwrap();
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.deauthorize_operator(accounts.bob, true), Err(Error::Unauthorized));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+
+            let events_before = ink::env::test::recorded_events().count();
+            assert_eq!(registry.deauthorize_operator(accounts.bob, true), Ok(0));
+            assert_eq!(ink::env::test::recorded_events().count() - events_before, 2);
+            assert!(!registry.is_operator_authorized(accounts.bob));
+            assert_eq!(registry.get_operator_vehicle_count(accounts.bob), 0);
+            for i in 0..2 {
+                assert_eq!(registry.get_vehicle(format!("AXI_00{i}")).unwrap().operator, None);
+            }
+        }
+
+        #[ink::test]
+        fn admin_messages_follow_roles() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            assert!(registry.has_role(Role::Admin, accounts.alice));
+
+            registry.grant_role(Role::FleetManager, accounts.bob).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.authorize_operator(accounts.django).unwrap();
+            assert_eq!(registry.set_max_speed(200), Err(Error::Unauthorized));
+            assert_eq!(registry.grant_role(Role::FleetManager, accounts.charlie), Err(Error::Unauthorized));
+
+            registry.renounce_role(Role::FleetManager).unwrap();
+            assert_eq!(registry.authorize_operator(accounts.eve), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            registry.set_role_admin(Role::Registrar, Role::FleetManager).unwrap();
+            registry.grant_role(Role::FleetManager, accounts.bob).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.grant_role(Role::Registrar, accounts.charlie).unwrap();
+            assert!(registry.has_role(Role::Registrar, accounts.charlie));
+            assert_eq!(registry.get_role_admin(Role::Registrar), Role::FleetManager);
+        }
+
+        #[ink::test]
+        fn contract_ownership_moves_in_two_steps() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+
+            registry.transfer_ownership(accounts.bob).unwrap();
+            assert_eq!(registry.get_owner(), Some(accounts.alice));
+            assert_eq!(registry.get_pending_owner(), Some(accounts.bob));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.accept_ownership(), Err(Error::Unauthorized));
+            assert_eq!(registry.transfer_ownership(accounts.charlie), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.accept_ownership().unwrap();
+            assert_eq!(registry.get_owner(), Some(accounts.bob));
+            assert_eq!(registry.get_pending_owner(), None);
+            assert!(registry.has_role(Role::Admin, accounts.bob));
+            assert!(!registry.has_role(Role::Admin, accounts.alice));
+            registry.set_max_speed(200).unwrap();
+
+            registry.renounce_ownership().unwrap();
+            assert_eq!(registry.get_owner(), None);
+            assert_eq!(registry.set_max_speed(250), Err(Error::Unauthorized));
+        }
+
+        #[derive(Encode, Decode, Debug, PartialEq)]
+        struct VehicleV2 {
+            vehicle: Vehicle,
+            fleet_tag: Option<String>,
+        }
+
+        impl Migrate for VehicleV2 {
+            const VERSION: u16 = 2;
+
+            fn migrate(version: u16, payload: &[u8]) -> Option<Self> {
+                match version {
+                    1 => Vehicle::decode(&mut &payload[..]).ok().map(|vehicle| VehicleV2 {
+                        vehicle,
+                        fleet_tag: None,
+                    }),
+                    _ => None,
+                }
+            }
+        }
+
+        #[ink::test]
+        fn stored_vehicles_migrate_to_new_layout() {
+            use axi_common::VersionedValue;
+            use ink::storage::traits::StorageKey;
+
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            let vehicle = registry.get_vehicle(String::from("AXI_001")).unwrap();
+            let entry = (registry.vehicles.key(), String::from("AXI_001"));
+
+            let stored: VersionedValue = ink::env::get_contract_storage(&entry).unwrap().unwrap();
+            assert_eq!(stored.version, 1);
+            let migrated = stored.decode::<VehicleV2>().unwrap();
+            assert_eq!(migrated, VehicleV2 { vehicle: vehicle.clone(), fleet_tag: None });
+
+            // Once rewritten in the new layout, the v1 code no longer reads it.
+            ink::env::set_contract_storage(&entry, &VersionedValue::new(&migrated));
+            let stored: VersionedValue = ink::env::get_contract_storage(&entry).unwrap().unwrap();
+            assert_eq!(stored.decode::<Vehicle>(), None);
+            assert_eq!(stored.decode::<VehicleV2>().unwrap().vehicle, vehicle);
+        }
+
+        #[ink::test]
+        fn upgrade_and_migration_are_admin_only() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            assert_eq!(registry.get_storage_version(), STORAGE_VERSION);
+            assert_eq!(registry.migrate_storage(), Err(Error::StorageUpToDate));
+
+            // Storage written by an older code version.
+            registry.storage_version = STORAGE_VERSION - 1;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.upgrade(Hash::from([1u8; 32])), Err(Error::Unauthorized));
+            assert_eq!(registry.migrate_storage(), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            registry.migrate_storage().unwrap();
+            assert_eq!(registry.get_storage_version(), STORAGE_VERSION);
+        }
+
+        #[ink::test]
+        fn guardians_pause_but_only_admins_unpause() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.grant_role(Role::Guardian, accounts.bob).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.pause(PauseScope::Registration), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.pause(PauseScope::Registration).unwrap();
+            assert_eq!(registry.unpause(PauseScope::Registration), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                registry.register_vehicle(registration("AXI_002", &vin(2), "AXI002")),
+                Err(Error::Paused)
+            );
+            registry.update_mileage(String::from("AXI_001"), 10, None).unwrap();
+
+            registry.pause(PauseScope::Mutation).unwrap();
+            assert_eq!(registry.update_mileage(String::from("AXI_001"), 20, None), Err(Error::Paused));
+            assert!(registry.is_paused(PauseScope::Mutation));
+
+            registry.unpause(PauseScope::Registration).unwrap();
+            registry.unpause(PauseScope::Mutation).unwrap();
+            registry.register_vehicle(registration("AXI_002", &vin(2), "AXI002")).unwrap();
+            registry.update_mileage(String::from("AXI_001"), 20, None).unwrap();
         }
 
-        #[ink(message)]
-        pub fn authorize_operator(&mut self, operator: AccountId) -> Result<(), String> {
-            if self.env().caller() != self.owner {
-                return Err(String::from("Unauthorized"));
-            }
+        #[ink::test]
+        fn paused_queries_return_paused() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.pause(PauseScope::Query).unwrap();
 
-            self.authorized_operators.insert(operator, &true);
-            Ok(())
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let vehicle_id = String::from("AXI_001");
+            assert_eq!(registry.get_owner_vehicles(accounts.alice, 0, 10), Err(Error::Paused));
+            assert_eq!(registry.list_vehicles(0, 10, VehicleFilter::default()), Err(Error::Paused));
+            assert_eq!(registry.get_location_history(vehicle_id.clone(), 0, u64::MAX, 10), Err(Error::Paused));
+            assert_eq!(registry.get_plate_history(vehicle_id.clone()), Err(Error::Paused));
+            assert_eq!(registry.get_ownership_history(vehicle_id.clone()), Err(Error::Paused));
+            assert_eq!(registry.get_service_due(vehicle_id.clone()), Err(Error::Paused));
+            assert_eq!(registry.get_calibration_history(String::from("SENSOR_001")), Err(Error::Paused));
+            // Point lookups stay available
+            assert!(registry.get_vehicle(vehicle_id).is_some());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            registry.unpause(PauseScope::Query).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.get_owner_vehicles(accounts.alice, 0, 10).unwrap().len(), 1);
         }
 
-        #[ink(message)]
-        pub fn deauthorize_operator(&mut self, operator: AccountId) -> Result<(), String> {
-            if self.env().caller() != self.owner {
-                return Err(String::from("Unauthorized"));
-            }
+        #[ink::test]
+        fn auditors_query_while_paused() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.grant_role(Role::Auditor, accounts.charlie).unwrap();
+            registry.pause(PauseScope::Query).unwrap();
 
-            self.authorized_operators.insert(operator, &false);
-            Ok(())
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(registry.get_owner_vehicles(accounts.alice, 0, 10).unwrap().len(), 1);
+            assert_eq!(registry.get_status_history(String::from("AXI_001"), 0, 10).unwrap().len(), 0);
         }
 
-        #[ink(message)]
-        pub fn get_vehicle(&self, vehicle_id: String) -> Option<Vehicle> {
-            self.vehicles.get(&vehicle_id)
-        }
+        #[ink::test]
+        fn best_effort_batch_reports_per_item_results() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
 
-        #[ink(message)]
-        pub fn get_vehicle_by_vin(&self, vin: String) -> Option<Vehicle> {
-            if let Some(vehicle_id) = self.vin_to_id.get(&vin) {
-                self.vehicles.get(&vehicle_id)
-            } else {
-                None
-            }
+            let results = registry
+                .register_vehicles(
+                    vec![
+                        registration("AXI_002", &vin(12), "AXI002"),
+                        registration("AXI_003", &vin(1), "AXI003"),
+                        registration("AXI_004", &vin(14), "AXI004"),
+                        registration("AXI_005", &vin(15), "AXI004"),
+                    ],
+                    BatchMode::BestEffort,
+                )
+                .unwrap();
+
+            assert_eq!(
+                results,
+                vec![Ok(()), Err(Error::DuplicateVin), Ok(()), Err(Error::DuplicateLicensePlate)]
+            );
+            assert_eq!(registry.get_vehicle_id_count(), 3);
+            assert!(registry.get_vehicle(String::from("AXI_004")).is_some());
+            assert!(registry.get_vehicle(String::from("AXI_005")).is_none());
         }
 
-        #[ink(message)]
-        pub fn get_vehicle_by_license(&self, license_plate: String) -> Option<Vehicle> {
-            if let Some(vehicle_id) = self.license_to_id.get(&license_plate) {
-                self.vehicles.get(&vehicle_id)
-            } else {
-                None
-            }
+        #[ink::test]
+        fn atomic_batch_writes_nothing_on_failure() {
+            let mut registry = VehicleRegistry::new();
+            let events_before = ink::env::test::recorded_events().count();
+
+            let results = registry
+                .register_vehicles(
+                    vec![
+                        registration("AXI_001", &vin(11), "AXI001"),
+                        registration("AXI_002", &vin(12), "AXI002"),
+                        registration("AXI_001", &vin(13), "AXI003"),
+                    ],
+                    BatchMode::Atomic,
+                )
+                .unwrap();
+
+            assert_eq!(
+                results,
+                vec![Err(Error::BatchAborted), Err(Error::BatchAborted), Err(Error::VehicleAlreadyRegistered)]
+            );
+            assert_eq!(registry.get_vehicle_id_count(), 0);
+            assert_eq!(registry.get_stats().total_vehicles, 0);
+            assert_eq!(ink::env::test::recorded_events().count(), events_before);
+
+            let results = registry
+                .register_vehicles(
+                    vec![
+                        registration("AXI_001", &vin(11), "AXI001"),
+                        registration("AXI_002", &vin(12), "AXI002"),
+                    ],
+                    BatchMode::Atomic,
+                )
+                .unwrap();
+            assert_eq!(results, vec![Ok(()), Ok(())]);
+            assert_eq!(registry.get_vehicle_id_count(), 2);
+
+            let results = registry
+                .register_sensors(
+                    vec![
+                        sensor_registration("GPS_001", "AXI_001"),
+                        sensor_registration("GPS_001", "AXI_002"),
+                    ],
+                    BatchMode::Atomic,
+                )
+                .unwrap();
+            assert_eq!(results, vec![Err(Error::BatchAborted), Err(Error::SensorAlreadyRegistered)]);
+            assert!(registry.get_sensor(String::from("GPS_001")).is_none());
+
+            let results = registry
+                .register_sensors(
+                    vec![
+                        sensor_registration("GPS_001", "AXI_001"),
+                        sensor_registration("GPS_002", "AXI_009"),
+                        sensor_registration("GPS_003", "AXI_002"),
+                    ],
+                    BatchMode::BestEffort,
+                )
+                .unwrap();
+            assert_eq!(results, vec![Ok(()), Err(Error::VehicleNotFound), Ok(())]);
+            assert_eq!(registry.get_vehicle_sensor_count(String::from("AXI_002")), 1);
         }
 
-        #[ink(message)]
-        pub fn get_sensor(&self, sensor_id: String) -> Option<Sensor> {
-            self.sensors.get(&sensor_id)
+        #[ink::test]
+        fn batch_size_is_capped() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            assert_eq!(registry.get_max_batch_size(), DEFAULT_MAX_BATCH_SIZE);
+
+            registry.set_max_batch_size(1).unwrap();
+            assert_eq!(
+                registry.register_vehicles(
+                    vec![
+                        registration("AXI_001", &vin(11), "AXI001"),
+                        registration("AXI_002", &vin(12), "AXI002"),
+                    ],
+                    BatchMode::BestEffort,
+                ),
+                Err(Error::BatchTooLarge)
+            );
+            assert_eq!(registry.set_max_batch_size(0), Err(Error::InvalidConfiguration));
+            assert_eq!(
+                registry.set_max_batch_size(MAX_BATCH_SIZE_LIMIT + 1),
+                Err(Error::InvalidConfiguration)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.set_max_batch_size(10), Err(Error::Unauthorized));
         }
 
-        #[ink(message)]
-        pub fn get_vehicle_sensors(&self, vehicle_id: String) -> Vec<String> {
-            self.vehicle_sensors.get(&vehicle_id).unwrap_or_default()
+        #[ink::test]
+        fn dids_are_bound_to_a_single_vehicle_or_sensor() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_sensor(sensor_registration("GPS_001", "AXI_001")).unwrap();
+
+            // Without a DID registry the identifier is not checked at all.
+            let mut unnamed = registration("AXI_003", &vin(3), "AXI003");
+            unnamed.did_identifier = String::new();
+            registry.register_vehicle(unnamed).unwrap();
+            assert!(registry.get_vehicle_by_did(String::new()).is_none());
+
+            // The binding check runs before the registry is called.
+            registry.set_did_registry(Some(accounts.django)).unwrap();
+            let mut reused = registration("AXI_002", &vin(2), "AXI002");
+            reused.did_identifier = String::from("did:peaq:vehicle:axi_001");
+            assert_eq!(registry.register_vehicle(reused), Err(Error::DidAlreadyBound));
+
+            let mut reused = sensor_registration("GPS_002", "AXI_001");
+            reused.did_identifier = String::from("did:peaq:sensor:gps_001");
+            assert_eq!(registry.register_sensor(reused), Err(Error::DidAlreadyBound));
+
+            assert_eq!(
+                registry
+                    .get_vehicle_by_did(String::from("did:peaq:vehicle:axi_001"))
+                    .map(|vehicle| vehicle.id),
+                Some(String::from("AXI_001"))
+            );
+            assert_eq!(
+                registry
+                    .get_sensor_by_did(String::from("did:peaq:sensor:gps_001"))
+                    .map(|sensor| sensor.id),
+                Some(String::from("GPS_001"))
+            );
+
+            // Deregistration releases the DIDs of the vehicle and its sensors.
+            registry.deregister_vehicle(String::from("AXI_001"), StatusReason::EndOfLife).unwrap();
+            assert!(registry.get_vehicle_by_did(String::from("did:peaq:vehicle:axi_001")).is_none());
+            assert!(registry.get_sensor_by_did(String::from("did:peaq:sensor:gps_001")).is_none());
+
+            registry.set_did_registry(None).unwrap();
+            let mut reused = registration("AXI_002", &vin(2), "AXI002");
+            reused.did_identifier = String::from("did:peaq:vehicle:axi_001");
+            registry.register_vehicle(reused).unwrap();
+            assert_eq!(
+                registry
+                    .get_vehicle_by_did(String::from("did:peaq:vehicle:axi_001"))
+                    .map(|vehicle| vehicle.id),
+                Some(String::from("AXI_002"))
+            );
         }
 
-        #[ink(message)]
-        pub fn get_owner_vehicles(&self, owner: AccountId) -> Vec<String> {
-            self.owner_vehicles.get(owner).unwrap_or_default()
+        #[ink::test]
+        fn did_registry_is_admin_configured() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            assert_eq!(registry.get_did_registry(), None);
+
+            registry.set_did_registry(Some(accounts.django)).unwrap();
+            assert_eq!(registry.get_did_registry(), Some(accounts.django));
+            registry.set_did_registry(None).unwrap();
+            assert_eq!(registry.get_did_registry(), None);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.set_did_registry(Some(accounts.bob)), Err(Error::Unauthorized));
         }
 
-        #[ink(message)]
-        pub fn get_operator_vehicles(&self, operator: AccountId) -> Vec<String> {
-            self.operator_vehicles.get(operator).unwrap_or_default()
+        #[ink::test]
+        fn did_suspension_outlives_service_visit() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.grant_role(Role::ServiceProvider, accounts.eve).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            registry.begin_service(vehicle_id.clone()).unwrap();
+
+            let vehicle = registry.get_vehicle(vehicle_id.clone()).unwrap();
+            assert!(registry.apply_did_status(vehicle, DidStatus::Suspended));
+
+            let records_before = registry.get_status_record_count(vehicle_id.clone());
+            registry.complete_service(vehicle_id.clone()).unwrap();
+
+            assert!(registry.get_service_visit(vehicle_id.clone()).is_none());
+            assert_eq!(registry.get_vehicle(vehicle_id.clone()).unwrap().status, VehicleStatus::Suspended);
+            assert_eq!(registry.get_status_record_count(vehicle_id), records_before);
+            assert_eq!(registry.get_stats().active_vehicles, 0);
         }
 
-        #[ink(message)]
-        pub fn get_stats(&self) -> RegistryStats {
-            self.stats.clone()
+        #[ink::test]
+        fn did_status_suspends_or_deregisters_vehicle() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry.register_vehicle(registration("AXI_002", &vin(2), "AXI002")).unwrap();
+            registry.register_sensor(sensor_registration("GPS_001", "AXI_001")).unwrap();
+            registry.register_sensor(sensor_registration("GPS_002", "AXI_002")).unwrap();
+
+            let vehicle = registry.get_vehicle(String::from("AXI_001")).unwrap();
+            assert!(!registry.apply_did_status(vehicle.clone(), DidStatus::Active));
+
+            let events_before = ink::env::test::recorded_events().count();
+            assert!(registry.apply_did_status(vehicle, DidStatus::Suspended));
+            assert_eq!(ink::env::test::recorded_events().count(), events_before + 2);
+            assert_eq!(
+                registry.get_vehicle(String::from("AXI_001")).unwrap().status,
+                VehicleStatus::Suspended
+            );
+            assert_eq!(
+                registry.get_sensor(String::from("GPS_001")).unwrap().status,
+                SensorStatus::Inactive
+            );
+            assert_eq!(registry.get_stats().active_vehicles, 1);
+            assert_eq!(registry.get_stats().active_sensors, 1);
+
+            let vehicle = registry.get_vehicle(String::from("AXI_001")).unwrap();
+            assert!(!registry.apply_did_status(vehicle, DidStatus::Suspended));
+
+            let vehicle = registry.get_vehicle(String::from("AXI_002")).unwrap();
+            assert!(registry.apply_did_status(vehicle, DidStatus::Revoked));
+            assert_eq!(
+                registry.get_vehicle(String::from("AXI_002")).unwrap().status,
+                VehicleStatus::Deregistered
+            );
+            assert_eq!(
+                registry.get_sensor(String::from("GPS_002")).unwrap().status,
+                SensorStatus::Retired
+            );
+            assert!(registry.get_vehicle_by_did(String::from("did:peaq:vehicle:axi_002")).is_none());
+            assert_eq!(registry.get_stats().total_vehicles, 1);
         }
 
-        #[ink(message)]
-        pub fn is_operator_authorized(&self, operator: AccountId) -> bool {
-            self.authorized_operators.get(operator).unwrap_or(false)
+        #[ink::test]
+        fn did_sync_requires_configured_registry() {
+            let mut registry = VehicleRegistry::new();
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+
+            assert_eq!(
+                registry.sync_did_status(String::from("AXI_001")),
+                Err(Error::DidRegistryNotConfigured)
+            );
+            assert_eq!(
+                registry.sync_did_statuses(vec![String::from("AXI_001"), String::from("AXI_404")]),
+                Ok(vec![Err(Error::DidRegistryNotConfigured), Err(Error::VehicleNotFound)])
+            );
         }
 
-        #[ink(message)]
-        pub fn is_vehicle_active(&self, vehicle_id: String) -> bool {
-            if let Some(vehicle) = self.vehicles.get(&vehicle_id) {
-                vehicle.status == VehicleStatus::Active
-            } else {
-                false
+        #[ink::test]
+        fn status_transitions_follow_the_table() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VehicleRegistry::new();
+            let vehicle_id = String::from("AXI_001");
+            registry.authorize_operator(accounts.charlie).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
+            registry
+                .assign_operator(vehicle_id.clone(), accounts.charlie, OperatorPermissions::all(), None)
+                .unwrap();
+
+            registry
+                .update_vehicle_status(vehicle_id.clone(), VehicleStatus::Inactive, StatusReason::Manual)
+                .unwrap();
+            assert_eq!(
+                registry.update_vehicle_status(vehicle_id.clone(), VehicleStatus::Inactive, StatusReason::Manual),
+                Err(Error::InvalidStatusTransition)
+            );
+            assert_eq!(
+                registry.update_vehicle_status(vehicle_id.clone(), VehicleStatus::Suspended, StatusReason::Manual),
+                Err(Error::StatusReasonRequired)
+            );
+            assert_eq!(
+                registry.update_vehicle_status(
+                    vehicle_id.clone(),
+                    VehicleStatus::Suspended,
+                    StatusReason::GeofenceViolation
+                ),
+                Err(Error::InvalidStatusReason)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                registry.update_vehicle_status(vehicle_id.clone(), VehicleStatus::Suspended, StatusReason::Theft),
+                Err(Error::StatusTransitionNotPermitted)
+            );
+            registry
+                .update_vehicle_status(vehicle_id.clone(), VehicleStatus::Active, StatusReason::Manual)
+                .unwrap();
+
+            // Only a fleet manager lifts a suspension, and only a registrar deregisters a suspended vehicle.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            registry
+                .update_vehicle_status(vehicle_id.clone(), VehicleStatus::Suspended, StatusReason::Compliance)
+                .unwrap();
+            for caller in [accounts.bob, accounts.charlie] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(caller);
+                assert_eq!(
+                    registry.update_vehicle_status(vehicle_id.clone(), VehicleStatus::Active, StatusReason::Manual),
+                    Err(Error::StatusTransitionNotPermitted)
+                );
             }
-        }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                registry.deregister_vehicle(vehicle_id.clone(), StatusReason::EndOfLife),
+                Err(Error::StatusTransitionNotPermitted)
+            );
 
-        #[ink(message)]
-        pub fn get_owner(&self) -> AccountId {
-            self.owner
-        }
-    }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                registry.deregister_vehicle(vehicle_id.clone(), StatusReason::Manual),
+                Err(Error::StatusReasonRequired)
+            );
+            registry.deregister_vehicle(vehicle_id.clone(), StatusReason::Compliance).unwrap();
+            assert_eq!(
+                registry.update_vehicle_status(vehicle_id.clone(), VehicleStatus::Active, StatusReason::Manual),
+                Err(Error::VehicleDeregistered)
+            );
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+            let history = registry.get_status_history(vehicle_id.clone(), 0, 10).unwrap();
+            assert_eq!(registry.get_status_record_count(vehicle_id), 4);
+            assert_eq!(
+                history
+                    .iter()
+                    .map(|record| (record.to.clone(), record.reason))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (VehicleStatus::Inactive, StatusReason::Manual),
+                    (VehicleStatus::Active, StatusReason::Manual),
+                    (VehicleStatus::Suspended, StatusReason::Compliance),
+                    (VehicleStatus::Deregistered, StatusReason::Compliance),
+                ]
+            );
+            assert_eq!(history[1].changed_by, accounts.charlie);
+            assert_eq!(history[3].from, VehicleStatus::Suspended);
+        }
 
         #[ink::test]
-        fn new_works() {
-            let registry = VehicleRegistry::new();
-            let stats = registry.get_stats();
-            assert_eq!(stats.total_vehicles, 0);
-            assert_eq!(stats.active_vehicles, 0);
+        fn stats_stay_consistent_under_random_operations() {
+            for (contract, seed) in [0x9e37_79b9_7f4a_7c15u64, 0x2545_f491_4f6c_dd1d, 0xdead_beef_cafe_f00d]
+                .into_iter()
+                .enumerate()
+            {
+                // Each run gets its own contract account, and with it fresh storage.
+                ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([contract as u8 + 1; 32]));
+                let mut registry = VehicleRegistry::new();
+                let mut rng = XorShift(seed);
+
+                for _ in 0..150 {
+                    let vehicle = rng.below(6) as u32;
+                    let vehicle_id = format!("AXI_00{vehicle}");
+                    let sensor_id = format!("SNS_00{}", rng.below(9));
+
+                    let _ = match rng.below(11) {
+                        0 | 1 => {
+                            let mut next = registration(&vehicle_id, &vin(vehicle), &format!("PLATE{vehicle}"));
+                            next.engine_type = EngineType::ALL[rng.below(4) as usize].clone();
+                            registry.register_vehicle(next)
+                        }
+                        2 | 3 => {
+                            let mut next = sensor_registration(&sensor_id, &vehicle_id);
+                            next.sensor_type = SensorType::ALL[rng.below(9) as usize].clone();
+                            registry.register_sensor(next)
+                        }
+                        4 => {
+                            let status = VehicleStatus::ALL[rng.below(4) as usize].clone();
+                            registry.update_vehicle_status(vehicle_id, status, StatusReason::Theft)
+                        }
+                        5 => {
+                            let status = SensorStatus::LIVE[rng.below(4) as usize].clone();
+                            registry.update_sensor_status(sensor_id, status)
+                        }
+                        6 => registry.remove_sensor(sensor_id),
+                        7 => registry.reassign_sensor(sensor_id, vehicle_id),
+                        8 => registry.deregister_vehicle(vehicle_id, StatusReason::EndOfLife),
+                        9 => registry
+                            .begin_service(vehicle_id.clone())
+                            .or_else(|_| registry.complete_service(vehicle_id)),
+                        _ => match registry.registered_vehicle(&vehicle_id) {
+                            Ok(record) => {
+                                let did_status = [DidStatus::Suspended, DidStatus::Revoked][rng.below(2) as usize].clone();
+                                registry.apply_did_status(record, did_status);
+                                Ok(())
+                            }
+                            Err(error) => Err(error),
+                        },
+                    };
+
+                    assert_eq!(registry.get_stats(), expected_stats(&registry), "seed {seed:#x}");
+                }
+
+                // Rebuilding from scratch lands on the same numbers.
+                registry.pause(PauseScope::Registration).unwrap();
+                registry.pause(PauseScope::Mutation).unwrap();
+                let expected = expected_stats(&registry);
+                registry.stats = StatsTotals::default();
+                registry.engine_type_counts.insert(EngineType::Diesel, &42);
+
+                let mut cursor = Some(0);
+                while let Some(next) = cursor {
+                    cursor = registry.recompute_stats(next, 4).unwrap();
+                }
+                assert_eq!(registry.get_stats(), expected, "seed {seed:#x}");
+            }
         }
 
         #[ink::test]
-        fn register_vehicle_works() {
+        fn recompute_stats_is_admin_only_and_requires_pause() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
             let mut registry = VehicleRegistry::new();
-            
-            let registration = VehicleRegistration {
-                vehicle_id: String::from("AXI_001"),
-                did_identifier: String::from("did:peaq:vehicle:axi_001"),
-                vin: String::from("TEST123456789"),
-                make: String::from("Tesla"),
-                model: String::from("Model 3"),
-                year: 2023,
-                license_plate: String::from("AXI001"),
-                engine_type: EngineType::Electric,
-                battery_capacity: Some(75),
-            };
+            registry.register_vehicle(registration("AXI_001", &vin(1), "AXI001")).unwrap();
 
-            let result = registry.register_vehicle(registration);
+            assert_eq!(registry.recompute_stats(0, 10), Err(Error::NotPaused));
+            registry.pause(PauseScope::Registration).unwrap();
+            assert_eq!(registry.recompute_stats(0, 10), Err(Error::NotPaused));
+            registry.pause(PauseScope::Mutation).unwrap();
+            assert_eq!(registry.recompute_stats(0, 10), Ok(None));
 
-            assert!(result.is_ok());
-            assert!(registry.get_vehicle(String::from("AXI_001")).is_some());
-            
             let stats = registry.get_stats();
             assert_eq!(stats.total_vehicles, 1);
-            assert_eq!(stats.active_vehicles, 1);
-            assert_eq!(stats.electric_vehicles, 1);
+            assert!(stats.vehicles_by_engine_type.contains(&(EngineType::Electric, 1)));
+            assert!(stats.vehicles_by_status.contains(&(VehicleStatus::Active, 1)));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.recompute_stats(0, 10), Err(Error::Unauthorized));
         }
 
         #[ink::test]
-        fn duplicate_vehicle_fails() {
-            let mut registry = VehicleRegistry::new();
-            
-            let registration1 = VehicleRegistration {
-                vehicle_id: String::from("AXI_001"),
-                did_identifier: String::from("did:peaq:vehicle:axi_001"),
-                vin: String::from("TEST123456789"),
+        fn error_indices_are_stable() {
+            assert_eq!(Encode::encode(&Error::VehicleNotFound), [0]);
+            assert_eq!(Encode::encode(&Error::OperatorNotAuthorized), [6]);
+            assert_eq!(Encode::encode(&Error::Unauthorized), [7]);
+            assert_eq!(Encode::encode(&Error::MileageRollback), [8]);
+            assert_eq!(Encode::encode(&Error::InvalidStatusTransition), [13]);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use did_registry::did_registry::{self as did, DidRegistry, DidRegistryRef};
+        use ink_e2e::ContractsBackend;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        fn vehicle_metadata() -> did::VehicleMetadata {
+            did::VehicleMetadata {
+                vin: String::new(),
                 make: String::from("Tesla"),
                 model: String::from("Model 3"),
                 year: 2023,
-                license_plate: String::from("AXI001"),
-                engine_type: EngineType::Electric,
-                battery_capacity: Some(75),
-            };
-            
-            let registration2 = VehicleRegistration {
-                vehicle_id: String::from("AXI_001"),
-                did_identifier: String::from("did:peaq:vehicle:axi_002"),
-                vin: String::from("TEST987654321"),
-                make: String::from("BMW"),
-                model: String::from("i3"),
-                year: 2023,
-                license_plate: String::from("AXI002"),
-                engine_type: EngineType::Electric,
-                battery_capacity: Some(42),
-            };
-
-            let result1 = registry.register_vehicle(registration1);
-            assert!(result1.is_ok());
-
-            let result2 = registry.register_vehicle(registration2);
-            assert!(result2.is_err());
+                license_plate: String::new(),
+                engine_type: did::EngineType::Electric,
+            }
         }
 
-        #[ink::test]
-        fn register_sensor_works() {
-            let mut registry = VehicleRegistry::new();
-            
-            let vehicle_registration = VehicleRegistration {
-                vehicle_id: String::from("AXI_001"),
-                did_identifier: String::from("did:peaq:vehicle:axi_001"),
-                vin: String::from("TEST123456789"),
+        fn registration(vehicle_id: &str, did_identifier: &str, serial: u32) -> VehicleRegistration {
+            let mut vin = format!("5YJ3E1EA0PF{serial:06}").into_bytes();
+            vin[VIN_CHECK_DIGIT_POSITION] = vin_check_digit(&vin).unwrap();
+            VehicleRegistration {
+                vehicle_id: String::from(vehicle_id),
+                did_identifier: String::from(did_identifier),
+                vin: String::from_utf8(vin).unwrap(),
                 make: String::from("Tesla"),
                 model: String::from("Model 3"),
                 year: 2023,
-                license_plate: String::from("AXI001"),
+                license_plate: format!("E2E{serial:03}"),
                 engine_type: EngineType::Electric,
                 battery_capacity: Some(75),
-            };
+            }
+        }
 
-            registry.register_vehicle(vehicle_registration).unwrap();
+        #[ink_e2e::test]
+        async fn verify_did_rejects_unusable_dids<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let mut did_constructor = DidRegistryRef::new();
+            let did_contract = client
+                .instantiate("did_registry", &ink_e2e::alice(), &mut did_constructor)
+                .submit()
+                .await
+                .expect("did_registry instantiate failed");
+            let mut did_call = did_contract.call_builder::<DidRegistry>();
 
-            let sensor_registration = SensorRegistration {
-                sensor_id: String::from("GPS_001"),
-                did_identifier: String::from("did:peaq:sensor:gps_001"),
-                vehicle_id: String::from("AXI_001"),
-                sensor_type: SensorType::Gps,
-                manufacturer: String::from("Bosch"),
-                model: String::from("GPS-2023"),
-                accuracy: String::from("±1m"),
-            };
+            let mut constructor = VehicleRegistryRef::new();
+            let contract = client
+                .instantiate("vehicle_registry", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("vehicle_registry instantiate failed");
+            let mut call = contract.call_builder::<VehicleRegistry>();
 
-            let result = registry.register_sensor(sensor_registration);
+            client
+                .call(&ink_e2e::alice(), &call.set_did_registry(Some(did_contract.account_id)))
+                .submit()
+                .await
+                .expect("set_did_registry failed");
 
-            assert!(result.is_ok());
-            assert!(registry.get_sensor(String::from("GPS_001")).is_some());
-            
-            let sensors = registry.get_vehicle_sensors(String::from("AXI_001"));
-            assert_eq!(sensors.len(), 1);
-            assert_eq!(sensors[0], "GPS_001");
-        }
+            // A revoked DID cannot back a registration.
+            let revoked = String::from("did:peaq:vehicle:revoked");
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &did_call.create_vehicle_did(revoked.clone(), [0u8; 32], String::new(), vehicle_metadata()),
+                )
+                .submit()
+                .await
+                .expect("create_vehicle_did failed");
+            client
+                .call(&ink_e2e::alice(), &did_call.revoke_did(revoked.clone(), String::from("stolen")))
+                .submit()
+                .await
+                .expect("revoke_did failed");
+            let result = client
+                .call(&ink_e2e::alice(), &call.register_vehicle(registration("AXI_001", &revoked, 1)))
+                .dry_run()
+                .await?;
+            assert_eq!(result.return_value(), Err(Error::DidNotActive));
 
-        #[ink::test]
-        fn operator_assignment_works() {
-            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            let mut registry = VehicleRegistry::new();
-            
-            let registration = VehicleRegistration {
-                vehicle_id: String::from("AXI_001"),
-                did_identifier: String::from("did:peaq:vehicle:axi_001"),
-                vin: String::from("TEST123456789"),
-                make: String::from("Tesla"),
-                model: String::from("Model 3"),
-                year: 2023,
-                license_plate: String::from("AXI001"),
-                engine_type: EngineType::Electric,
-                battery_capacity: Some(75),
-            };
+            // A sensor DID cannot back a vehicle.
+            let sensor = String::from("did:peaq:sensor:gps");
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &did_call.create_sensor_did(
+                        sensor.clone(),
+                        [0u8; 32],
+                        String::new(),
+                        did::SensorMetadata {
+                            sensor_type: String::from("GPS"),
+                            manufacturer: String::from("Bosch"),
+                            model: String::from("G1"),
+                            accuracy: String::from("1m"),
+                            parent_vehicle_did: None,
+                        },
+                    ),
+                )
+                .submit()
+                .await
+                .expect("create_sensor_did failed");
+            let result = client
+                .call(&ink_e2e::alice(), &call.register_vehicle(registration("AXI_002", &sensor, 2)))
+                .dry_run()
+                .await?;
+            assert_eq!(result.return_value(), Err(Error::DidEntityMismatch));
 
-            registry.register_vehicle(registration).unwrap();
-            registry.authorize_operator(accounts.bob).unwrap();
-            
-            let result = registry.assign_operator(String::from("AXI_001"), accounts.bob);
-            assert!(result.is_ok());
+            // Someone else's DID cannot be claimed without an authorization.
+            let foreign = String::from("did:peaq:vehicle:foreign");
+            client
+                .call(
+                    &ink_e2e::bob(),
+                    &did_call.create_vehicle_did(foreign.clone(), [0u8; 32], String::new(), vehicle_metadata()),
+                )
+                .submit()
+                .await
+                .expect("create_vehicle_did failed");
+            let result = client
+                .call(&ink_e2e::alice(), &call.register_vehicle(registration("AXI_003", &foreign, 3)))
+                .dry_run()
+                .await?;
+            assert_eq!(result.return_value(), Err(Error::DidNotControlled));
 
-            let vehicle = registry.get_vehicle(String::from("AXI_001")).unwrap();
-            assert_eq!(vehicle.operator, Some(accounts.bob));
+            Ok(())
         }
 
-        #[ink::test]
-        fn location_update_works() {
-            let mut registry = VehicleRegistry::new();
-            
-            let registration = VehicleRegistration {
-                vehicle_id: String::from("AXI_001"),
-                did_identifier: String::from("did:peaq:vehicle:axi_001"),
-                vin: String::from("TEST123456789"),
-                make: String::from("Tesla"),
-                model: String::from("Model 3"),
-                year: 2023,
-                license_plate: String::from("AXI001"),
-                engine_type: EngineType::Electric,
-                battery_capacity: Some(75),
-            };
+        #[ink_e2e::test]
+        async fn sync_did_status_follows_revocation<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let mut did_constructor = DidRegistryRef::new();
+            let did_contract = client
+                .instantiate("did_registry", &ink_e2e::alice(), &mut did_constructor)
+                .submit()
+                .await
+                .expect("did_registry instantiate failed");
+            let mut did_call = did_contract.call_builder::<DidRegistry>();
 
-            registry.register_vehicle(registration).unwrap();
+            let mut constructor = VehicleRegistryRef::new();
+            let contract = client
+                .instantiate("vehicle_registry", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("vehicle_registry instantiate failed");
+            let mut call = contract.call_builder::<VehicleRegistry>();
 
-            let latitude = -1234567; // -1.234567 degrees scaled by 1000000
-            let longitude = 36987654; // 36.987654 degrees scaled by 1000000
+            client
+                .call(&ink_e2e::alice(), &call.set_did_registry(Some(did_contract.account_id)))
+                .submit()
+                .await
+                .expect("set_did_registry failed");
 
-            let result = registry.update_location(String::from("AXI_001"), latitude, longitude);
-            assert!(result.is_ok());
+            let did_identifier = String::from("did:peaq:vehicle:axi_001");
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &did_call.create_vehicle_did(did_identifier.clone(), [0u8; 32], String::new(), vehicle_metadata()),
+                )
+                .submit()
+                .await
+                .expect("create_vehicle_did failed");
+            client
+                .call(&ink_e2e::alice(), &call.register_vehicle(registration("AXI_001", &did_identifier, 1)))
+                .submit()
+                .await
+                .expect("register_vehicle failed");
 
-            let vehicle = registry.get_vehicle(String::from("AXI_001")).unwrap();
-            assert!(vehicle.location.is_some());
-            
-            let location = vehicle.location.unwrap();
-            assert_eq!(location.latitude, latitude);
-            assert_eq!(location.longitude, longitude);
+            // Nothing to do while the DID is active.
+            let synced = client
+                .call(&ink_e2e::alice(), &call.sync_did_status(String::from("AXI_001")))
+                .submit()
+                .await
+                .expect("sync_did_status failed");
+            assert_eq!(synced.return_value(), Ok(false));
+
+            client
+                .call(&ink_e2e::alice(), &did_call.revoke_did(did_identifier, String::from("stolen")))
+                .submit()
+                .await
+                .expect("revoke_did failed");
+            let synced = client
+                .call(&ink_e2e::bob(), &call.sync_did_status(String::from("AXI_001")))
+                .submit()
+                .await
+                .expect("sync_did_status failed");
+            assert_eq!(synced.return_value(), Ok(true));
+
+            let vehicle = client
+                .call(&ink_e2e::alice(), &call.get_vehicle(String::from("AXI_001")))
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("vehicle record is kept");
+            assert_eq!(vehicle.status, VehicleStatus::Deregistered);
+
+            let history = client
+                .call(&ink_e2e::alice(), &call.get_status_history(String::from("AXI_001"), 0, 10))
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("queries are not paused");
+            assert_eq!(history.last().map(|record| record.reason), Some(StatusReason::DidRevoked));
+
+            Ok(())
         }
     }
 }
\ No newline at end of file